@@ -0,0 +1,66 @@
+//! Time of a step, serial and parallel, for flocks of 1k to 1M boids at a constant density.
+//!
+//! `cargo bench -p boids-core --features parallel --bench parallel [-- COUNT...]`
+
+use std::time::Instant;
+
+use boids_core::{Scenario, Simulation, scenario::SpawnDistribution};
+
+/// Side of the square each boid has on average.
+const SPACING: f32 = 20.;
+
+fn flock(boid_count: usize, parallel: bool) -> Simulation {
+    let side = (boid_count as f32).sqrt() * SPACING;
+    let scenario = Scenario {
+        boid_count,
+        world_size: (side, side),
+        spawn: SpawnDistribution::Uniform,
+        ..Scenario::default()
+    };
+    let mut sim = scenario.build().unwrap();
+    sim.set_parallel(parallel);
+    sim
+}
+
+/// Mean time of a step in milliseconds, after a first untimed one.
+fn time_step(sim: &mut Simulation, steps: u32) -> f64 {
+    sim.step();
+    let start = Instant::now();
+    for _ in 0..steps {
+        sim.step();
+    }
+    start.elapsed().as_secs_f64() * 1e3 / f64::from(steps)
+}
+
+fn main() {
+    let mut counts: Vec<usize> = std::env::args()
+        .skip(1)
+        .filter(|arg| !arg.starts_with("--"))
+        .map(|arg| arg.parse().expect("boid counts are integers"))
+        .collect();
+    if counts.is_empty() {
+        counts = vec![1_000, 10_000, 100_000, 1_000_000];
+    }
+
+    println!(
+        "{} threads\n{:>10} {:>12} {:>12} {:>8}",
+        rayon::current_num_threads(),
+        "boids",
+        "serial ms",
+        "parallel ms",
+        "speedup"
+    );
+    for count in counts {
+        let steps = (100_000 / count).clamp(3, 50) as u32;
+        let mut serial = flock(count, false);
+        let mut parallel = flock(count, true);
+        let serial_ms = time_step(&mut serial, steps);
+        let parallel_ms = time_step(&mut parallel, steps);
+        assert_eq!(serial.state_hash(), parallel.state_hash());
+
+        println!(
+            "{count:>10} {serial_ms:>12.3} {parallel_ms:>12.3} {:>7.2}x",
+            serial_ms / parallel_ms
+        );
+    }
+}
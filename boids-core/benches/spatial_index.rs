@@ -0,0 +1,109 @@
+//! Time to rebuild each spatial index and to query it for every point,
+//! on the distributions flocks typically have, at a constant mean density.
+//!
+//! `cargo bench -p boids-core --bench spatial_index [-- COUNT...]`
+
+use std::time::Instant;
+
+use boids_core::{Vec2, params::FlockParams, quadtree::Aabb, rng::Rng, spatial::SpatialIndexKind};
+
+/// Side of the square each point has on average.
+const SPACING: f32 = 20.;
+/// Brute force queries are quadratic, they are skipped above this count.
+const MAX_BRUTE_FORCE: usize = 10_000;
+/// Neighbours of the topological mode.
+const K: usize = 7;
+
+/// `count` points in a square world of side `side`.
+type Distribution = fn(&mut Rng, usize, f32) -> Vec<Vec2>;
+
+fn uniform(rng: &mut Rng, count: usize, side: f32) -> Vec<Vec2> {
+    (0..count)
+        .map(|_| Vec2::new(rng.range_f32(0., side), rng.range_f32(0., side)))
+        .collect()
+}
+
+/// Every point in a disc of a tenth of the world area, as a spawned flock.
+fn disc(rng: &mut Rng, count: usize, side: f32) -> Vec<Vec2> {
+    let radius = side * (0.1 / std::f32::consts::PI).sqrt();
+    (0..count)
+        .map(|_| {
+            let angle = rng.range_f32(0., std::f32::consts::TAU);
+            let r = radius * rng.next_f32().sqrt();
+            Vec2::new(side / 2. + r * angle.cos(), side / 2. + r * angle.sin())
+        })
+        .collect()
+}
+
+/// Many small dense flocks scattered over the world, as after the flock split.
+fn flocks(rng: &mut Rng, count: usize, side: f32) -> Vec<Vec2> {
+    let centers = uniform(rng, (count / 200).max(1), side);
+    (0..count)
+        .map(|i| {
+            let center = centers[i % centers.len()];
+            let offset = Vec2::new(rng.range_f32(-60., 60.), rng.range_f32(-60., 60.));
+            center + offset
+        })
+        .collect()
+}
+
+/// Milliseconds taken by `f`.
+fn time(f: impl FnOnce()) -> f64 {
+    let start = Instant::now();
+    f();
+    start.elapsed().as_secs_f64() * 1e3
+}
+
+fn main() {
+    let mut counts: Vec<usize> = std::env::args()
+        .skip(1)
+        .filter(|arg| !arg.starts_with("--"))
+        .map(|arg| arg.parse().expect("point counts are integers"))
+        .collect();
+    if counts.is_empty() {
+        counts = vec![1_000, 10_000, 100_000];
+    }
+    let radius = FlockParams::default().perception_radius();
+    let distributions: [(&str, Distribution); 3] =
+        [("uniform", uniform), ("disc", disc), ("flocks", flocks)];
+
+    println!(
+        "{:>8} {:>8} {:>12} {:>11} {:>9} {:>10}",
+        "points", "layout", "index", "rebuild ms", "range ms", "k-near ms"
+    );
+    for count in counts {
+        for (name, distribution) in distributions {
+            let side = (count as f32).sqrt() * SPACING;
+            let bound = Aabb::from_min_max(Vec2::zero(), Vec2::new(side, side));
+            let positions = distribution(&mut Rng::new(1), count, side);
+
+            for kind in SpatialIndexKind::ALL {
+                if kind == SpatialIndexKind::BruteForce && count > MAX_BRUTE_FORCE {
+                    continue;
+                }
+                let mut index = kind.build(bound, radius);
+                index.rebuild(bound, &positions);
+                let rebuild = time(|| index.rebuild(bound, &positions));
+
+                let mut found = Vec::new();
+                let range = time(|| {
+                    for &pos in &positions {
+                        found.clear();
+                        index.query_range(&Aabb::from_center(pos, radius), &mut found);
+                    }
+                });
+                let nearest = time(|| {
+                    for &pos in &positions {
+                        found.clear();
+                        index.k_nearest(pos, K + 1, &mut found);
+                    }
+                });
+
+                println!(
+                    "{count:>8} {name:>8} {:>12} {rebuild:>11.3} {range:>9.2} {nearest:>10.2}",
+                    kind.name()
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,499 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    goal::{Attractor, Route},
+    integrator::Integrator,
+    math::{Vec2, Vec3},
+    obstacle::Obstacle,
+    params::FlockParams,
+    quadtree::Aabb,
+    species::Interaction,
+    steering::{Neighbourhood, bounce, collision_push, constrain_velocity, sees_toward, soft_turn},
+    world::{Boundary, World},
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Boid {
+    pub position: Vec2,
+    pub velocity: Vec2,
+    /// Position before the last physics step, used to interpolate rendering.
+    pub previous_position: Vec2,
+
+    pub size: f32,
+    /// Index of the species of the boid in the simulation.
+    pub(crate) species: usize,
+
+    /// Number of waypoints of the route already reached.
+    pub(crate) waypoint: usize,
+
+    pub(crate) color: Vec3,
+    pub(crate) avg_color: Vec3,
+
+    pub(crate) steering: Steering,
+}
+
+/// What the steering rules read of a boid, the perceiving one or a neighbour.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoidState {
+    pub position: Vec2,
+    pub velocity: Vec2,
+    pub size: f32,
+    pub avg_color: Vec3,
+}
+
+/// Sums a boid gathers during a step from its neighbours, the predators, the obstacles
+/// and the goals, turned into its acceleration once everything has been perceived.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Steering {
+    neighbourhood: Neighbourhood<Vec2>,
+    flee_force: Vec2,
+    obstacle_force: Vec2,
+    seek_force: Vec2,
+
+    avg_color_nominator: Vec3,
+    avg_color_denominator: f32,
+
+    /// Displacement out of the overlapping neighbours, applied before the forces.
+    push: Vec2,
+}
+
+impl Boid {
+    pub fn new(pos: (f32, f32), id: usize) -> Self {
+        let pos = pos.into();
+
+        let color = id_color(id);
+        Boid {
+            position: pos,
+            velocity: Vec2::zero(),
+            previous_position: pos,
+            size: 2.,
+            species: 0,
+            waypoint: 0,
+
+            color,
+            avg_color: color,
+
+            steering: Steering::default(),
+        }
+    }
+
+    /// Position between the previous and the current physics step, `alpha` in `[0, 1]`.
+    pub fn interpolated_position(&self, alpha: f32) -> Vec2 {
+        self.previous_position + (self.position - self.previous_position) * alpha
+    }
+
+    /// Whether `point` is inside the perception cone of full angle `fov`
+    /// centered on the heading. A boid at rest sees all around.
+    pub fn sees(&self, point: Vec2, fov: f32) -> bool {
+        self.sees_toward(point - self.position, fov)
+    }
+
+    /// Same as [`Boid::sees`] with the displacement from the boid to the point.
+    pub fn sees_toward(&self, to_point: Vec2, fov: f32) -> bool {
+        sees_toward(self.velocity, to_point, fov)
+    }
+
+    /// Own color, as derived from the boid id unless set otherwise.
+    pub fn color(&self) -> &Vec3 {
+        &self.color
+    }
+
+    pub fn set_color(&mut self, color: Vec3) {
+        self.color = color;
+        self.avg_color = color;
+    }
+
+    pub fn species(&self) -> usize {
+        self.species
+    }
+
+    pub fn set_species(&mut self, species: usize) {
+        self.species = species;
+    }
+
+    /// Color blended with the neighbours, the one that should be displayed.
+    pub fn avg_color(&self) -> &Vec3 {
+        &self.avg_color
+    }
+
+    /// Number of neighbours the boid flocked with during the last step.
+    pub fn neighbour_count(&self) -> u32 {
+        self.steering.neighbour_count()
+    }
+
+    /// Number of waypoints of the route already reached.
+    pub fn waypoint(&self) -> usize {
+        self.waypoint
+    }
+
+    pub fn set_waypoint(&mut self, waypoint: usize) {
+        self.waypoint = waypoint;
+    }
+
+    pub fn state(&self) -> BoidState {
+        BoidState {
+            position: self.position,
+            velocity: self.velocity,
+            size: self.size,
+            avg_color: self.avg_color,
+        }
+    }
+}
+
+impl Steering {
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Number of neighbours taken into account by the cohesion rule.
+    pub fn neighbour_count(&self) -> u32 {
+        self.neighbourhood.neighbour_count()
+    }
+
+    /// Displacement out of the neighbours overlapping the boid.
+    pub fn push(&self) -> Vec2 {
+        self.push
+    }
+
+    /// Steering acceleration at `position` with `velocity`, bounded by `params.max_force`.
+    pub fn acceleration(&self, params: &FlockParams, position: Vec2, velocity: Vec2) -> Vec2 {
+        let steering = self.separation_acceleration(params)
+            + self.alignement_acceleration(params, velocity)
+            + self.cohesion_acceleration(params, position)
+            + self.flee_acceleration(params)
+            + self.obstacle_acceleration(params)
+            + self.seek_acceleration(params);
+        steering.truncated(params.max_force)
+    }
+
+    /// Integrate the boid at `position` with `velocity` over `dt`.
+    pub fn apply_forces(
+        &self,
+        params: &FlockParams,
+        integrator: Integrator,
+        dt: f32,
+        position: &mut Vec2,
+        velocity: &mut Vec2,
+    ) {
+        let old_velocity = *velocity;
+        let (new_position, new_velocity) =
+            integrator.integrate(*position, *velocity, dt, |position, velocity| {
+                self.acceleration(params, position, velocity)
+            });
+        *position = new_position;
+        *velocity = constrain_velocity(params, old_velocity, new_velocity, dt);
+    }
+
+    /// One directional perception of `other` by `me`, through the rules `interaction` allows.
+    pub fn perceive(
+        &mut self,
+        params: &FlockParams,
+        world: &World,
+        interaction: Interaction,
+        me: BoidState,
+        other: BoidState,
+    ) {
+        if interaction.blends() {
+            self.perceive_color(params, world, me, other);
+        }
+        if interaction.separates() {
+            self.perceive_separation(params, world, me, other);
+        } else if interaction == Interaction::Avoid {
+            self.perceive_avoidance(params, world, me, other);
+        }
+        if interaction.aligns() {
+            self.perceive_alignement(params, world, me, other);
+        }
+        if interaction.coheres() {
+            self.perceive_cohesion(params, world, me, other);
+        }
+    }
+
+    pub fn perceive_separation(
+        &mut self,
+        params: &FlockParams,
+        world: &World,
+        me: BoidState,
+        other: BoidState,
+    ) {
+        let to_other = world.offset(me.position, other.position);
+        self.neighbourhood
+            .perceive_separation(params, me.velocity, to_other);
+    }
+
+    pub fn perceive_avoidance(
+        &mut self,
+        params: &FlockParams,
+        world: &World,
+        me: BoidState,
+        other: BoidState,
+    ) {
+        let to_other = world.offset(me.position, other.position);
+        self.neighbourhood
+            .perceive_avoidance(params, me.velocity, to_other);
+    }
+
+    /// Half of the displacement pushing `me` out of `other` when they overlap.
+    pub fn perceive_collision(&mut self, world: &World, me: BoidState, other: BoidState) {
+        let away = world.offset(other.position, me.position);
+        self.push += collision_push(away, me.size + other.size);
+    }
+
+    pub fn separation_acceleration(&self, params: &FlockParams) -> Vec2 {
+        self.neighbourhood.separation_acceleration(params)
+    }
+
+    pub fn perceive_alignement(
+        &mut self,
+        params: &FlockParams,
+        world: &World,
+        me: BoidState,
+        other: BoidState,
+    ) {
+        let to_other = world.offset(me.position, other.position);
+        self.neighbourhood
+            .perceive_alignement(params, me.velocity, to_other, other.velocity);
+    }
+
+    pub fn alignement_acceleration(&self, params: &FlockParams, velocity: Vec2) -> Vec2 {
+        self.neighbourhood.alignement_acceleration(params, velocity)
+    }
+
+    pub fn perceive_cohesion(
+        &mut self,
+        params: &FlockParams,
+        world: &World,
+        me: BoidState,
+        other: BoidState,
+    ) {
+        let to_other = world.offset(me.position, other.position);
+        self.neighbourhood
+            .perceive_cohesion(params, me.velocity, me.position, to_other);
+    }
+
+    pub fn cohesion_acceleration(&self, params: &FlockParams, position: Vec2) -> Vec2 {
+        self.neighbourhood.cohesion_acceleration(params, position)
+    }
+
+    /// Flee from a predator at `predator`, the closer the stronger.
+    pub fn perceive_predator(
+        &mut self,
+        params: &FlockParams,
+        world: &World,
+        position: Vec2,
+        predator: Vec2,
+    ) {
+        let away = world.offset(predator, position);
+        let dist = away.length();
+        if dist < params.flee_radius && dist > 0. {
+            self.flee_force += away / dist * (1. - dist / params.flee_radius);
+        }
+    }
+
+    pub fn flee_acceleration(&self, params: &FlockParams) -> Vec2 {
+        self.flee_force * params.flee_factor
+    }
+
+    /// Potential field pushing away from the obstacles closer than `params.obstacle_margin`.
+    pub fn perceive_obstacles(
+        &mut self,
+        params: &FlockParams,
+        world: &World,
+        position: Vec2,
+        size: f32,
+        obstacles: &[Obstacle],
+    ) {
+        let margin = params.obstacle_margin + size;
+        for obstacle in obstacles {
+            let position = image_near(world, obstacle, position);
+            if obstacle.bounding_box().distance_sq_to(position) >= margin * margin {
+                continue;
+            }
+            let (dist, normal) = obstacle.signed_distance(position);
+            if dist < margin {
+                self.obstacle_force += normal * (1. - dist / margin).min(1.);
+            }
+        }
+    }
+
+    pub fn obstacle_acceleration(&self, params: &FlockParams) -> Vec2 {
+        self.obstacle_force * params.obstacle_factor
+    }
+
+    pub fn perceive_attractor(
+        &mut self,
+        params: &FlockParams,
+        world: &World,
+        me: BoidState,
+        attractor: &Attractor,
+    ) {
+        if !attractor.reaches(world.distance_sq(me.position, attractor.position)) {
+            return;
+        }
+        if attractor.weight >= 0. {
+            let seek = seek(params, world, me.position, me.velocity, attractor.position);
+            self.seek_force += seek * attractor.weight;
+        } else {
+            let evade = evade(params, world, me.position, me.velocity, attractor.position);
+            self.seek_force += evade * -attractor.weight;
+        }
+    }
+
+    /// Seek the waypoint of `route` after the `waypoint` already reached,
+    /// moving on to the next one when reached.
+    pub fn follow_route(
+        &mut self,
+        params: &FlockParams,
+        world: &World,
+        me: BoidState,
+        waypoint: &mut usize,
+        route: &Route,
+    ) {
+        let Some(mut target) = route.target(*waypoint) else {
+            return;
+        };
+        if world.distance_sq(me.position, target) < params.waypoint_radius * params.waypoint_radius
+            && (route.looping || *waypoint + 1 < route.waypoints.len())
+        {
+            *waypoint += 1;
+            if let Some(next) = route.target(*waypoint) {
+                target = next;
+            }
+        }
+        self.seek_force += seek(params, world, me.position, me.velocity, target);
+    }
+
+    pub fn seek_acceleration(&self, params: &FlockParams) -> Vec2 {
+        self.seek_force * params.seek_factor
+    }
+
+    pub fn perceive_color(
+        &mut self,
+        params: &FlockParams,
+        world: &World,
+        me: BoidState,
+        other: BoidState,
+    ) {
+        let dist_sq = world.distance_sq(me.position, other.position);
+        if params.reaches(dist_sq, params.cohesion * params.cohesion) {
+            let dist = dist_sq.sqrt().max(0.00001);
+
+            self.avg_color_nominator += other.avg_color / dist;
+            self.avg_color_denominator += dist.recip();
+        }
+    }
+
+    /// Color of the neighbours weighted by closeness, `own` when there are none.
+    pub fn blended_color(&self, own: Vec3) -> Vec3 {
+        if self.avg_color_denominator > 0. {
+            self.avg_color_nominator / self.avg_color_denominator
+        } else {
+            own
+        }
+    }
+}
+
+/// Keep a boid inside the world, or bring it back in, depending on the boundary.
+pub(crate) fn handle_border(
+    position: &mut Vec2,
+    velocity: &mut Vec2,
+    previous_position: &mut Vec2,
+    size: f32,
+    params: &FlockParams,
+    world: &World,
+) {
+    match world.boundary {
+        Boundary::SoftTurn => soft_turn(position, velocity, size, params, world),
+        Boundary::Bounce => bounce(position, velocity, size, world),
+        Boundary::Wrap => {
+            let wrapped = world.wrap(*position);
+            // keep the interpolation between steps continuous
+            *previous_position += wrapped - *position;
+            *position = wrapped;
+        }
+    }
+}
+
+/// Reynolds arrive : steering from `position` at `velocity` toward `target` at full speed,
+/// slowing down within `params.arrive_radius`.
+fn seek(params: &FlockParams, world: &World, position: Vec2, velocity: Vec2, target: Vec2) -> Vec2 {
+    let offset = world.offset(position, target);
+    let dist = offset.length();
+    if dist <= 0. {
+        return -velocity;
+    }
+    let speed = params.max_speed * (dist / params.arrive_radius).min(1.);
+    offset / dist * speed - velocity
+}
+
+/// Steering from `position` at `velocity` away from `threat` at full speed.
+fn evade(
+    params: &FlockParams,
+    world: &World,
+    position: Vec2,
+    velocity: Vec2,
+    threat: Vec2,
+) -> Vec2 {
+    let offset = world.offset(threat, position);
+    if offset.length_sq() <= 0. {
+        return Vec2::zero();
+    }
+    offset.normalized() * params.max_speed - velocity
+}
+
+/// Push a disc of radius `size` out of the obstacles it overlaps,
+/// cancelling the part of `velocity` going into them.
+pub(crate) fn resolve_obstacle_collisions(
+    position: &mut Vec2,
+    velocity: &mut Vec2,
+    size: f32,
+    world: &World,
+    obstacles: &[Obstacle],
+) {
+    for obstacle in obstacles {
+        let image = image_near(world, obstacle, *position);
+        if obstacle.bounding_box().distance_sq_to(image) >= size * size {
+            continue;
+        }
+        let (dist, normal) = obstacle.signed_distance(image);
+        if dist < size {
+            *position += normal * (size - dist);
+            let inward = velocity.dot(normal);
+            if inward < 0. {
+                *velocity -= normal * inward;
+            }
+        }
+    }
+}
+
+/// Copy of `position` nearest to `obstacle`, across the edges when the world wraps.
+fn image_near(world: &World, obstacle: &Obstacle, position: Vec2) -> Vec2 {
+    if !world.wraps() {
+        return position;
+    }
+    let Aabb { min, max } = obstacle.bounding_box();
+    let center = (min + max) * 0.5;
+    center + world.offset(center, position)
+}
+
+/// Default color of the boid `id`, going around the hues every 16 boids.
+pub fn id_color(id: usize) -> Vec3 {
+    hue_to_rgb(id as f32 * 4. * std::f32::consts::FRAC_PI_2 / 32.).into()
+}
+
+fn hue_to_rgb(h: f32) -> [f32; 3] {
+    let h = h % (2. * std::f32::consts::PI);
+    let c = 1.0;
+    let h_prime = h / (std::f32::consts::FRAC_PI_3);
+    let x = c * (1.0 - ((h_prime % 2.0) - 1.0).abs());
+
+    match h_prime as u32 {
+        0 => [c, x, 0.0],
+        1 => [x, c, 0.0],
+        2 => [0.0, c, x],
+        3 => [0.0, x, c],
+        4 => [x, 0.0, c],
+        5 => [c, 0.0, x],
+        _ => [1.0, 0.0, 0.0], // fallback (shouldn't happen)
+    }
+}
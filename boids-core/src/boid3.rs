@@ -0,0 +1,118 @@
+use crate::{
+    boid::id_color,
+    integrator::Integrator,
+    math::Vec3,
+    params::FlockParams,
+    steering::{Neighbourhood, bounce, collision_push, constrain_velocity, sees_toward, soft_turn},
+    world::{Boundary, World},
+};
+
+/// Boid of the 3D mode, following the separation, alignement and cohesion rules of [`crate::Boid`].
+#[derive(Debug, Clone)]
+pub struct Boid3 {
+    pub position: Vec3,
+    pub velocity: Vec3,
+    /// Position before the last physics step, used to interpolate rendering.
+    pub previous_position: Vec3,
+
+    pub size: f32,
+
+    neighbourhood: Neighbourhood<Vec3>,
+
+    color: Vec3,
+}
+
+impl Boid3 {
+    pub fn new(pos: Vec3, id: usize) -> Self {
+        Boid3 {
+            position: pos,
+            velocity: Vec3::zero(),
+            previous_position: pos,
+            size: 2.,
+
+            neighbourhood: Neighbourhood::default(),
+
+            color: id_color(id),
+        }
+    }
+
+    /// Position between the previous and the current physics step, `alpha` in `[0, 1]`.
+    pub fn interpolated_position(&self, alpha: f32) -> Vec3 {
+        self.previous_position + (self.position - self.previous_position) * alpha
+    }
+
+    /// Whether `point` is inside the perception cone of full angle `fov`
+    /// centered on the heading. A boid at rest sees all around.
+    pub fn sees(&self, point: Vec3, fov: f32) -> bool {
+        sees_toward(self.velocity, point - self.position, fov)
+    }
+
+    pub fn color(&self) -> &Vec3 {
+        &self.color
+    }
+
+    pub fn set_color(&mut self, color: Vec3) {
+        self.color = color;
+    }
+}
+
+impl Boid3 {
+    pub fn handle_border_colision(&mut self, params: &FlockParams, world: &World<Vec3>) {
+        match world.boundary {
+            Boundary::SoftTurn => soft_turn(
+                &mut self.position,
+                &mut self.velocity,
+                self.size,
+                params,
+                world,
+            ),
+            Boundary::Bounce => bounce(&mut self.position, &mut self.velocity, self.size, world),
+            Boundary::Wrap => {
+                let wrapped = world.wrap(self.position);
+                // keep the interpolation between steps continuous
+                self.previous_position += wrapped - self.position;
+                self.position = wrapped;
+            }
+        }
+    }
+
+    pub fn reset_forces(&mut self) {
+        self.neighbourhood = Neighbourhood::default();
+    }
+
+    /// Steering acceleration the boid would have at `position` with `velocity`,
+    /// the neighbourhood gathered by [`Boid3::perceive`] being frozen.
+    /// Bounded by `params.max_force`.
+    pub fn acceleration(&self, params: &FlockParams, position: Vec3, velocity: Vec3) -> Vec3 {
+        let steering = self.neighbourhood.separation_acceleration(params)
+            + self.neighbourhood.alignement_acceleration(params, velocity)
+            + self.neighbourhood.cohesion_acceleration(params, position);
+        steering.truncated(params.max_force)
+    }
+
+    pub fn apply_forces(&mut self, params: &FlockParams, integrator: Integrator, dt: f32) {
+        let old_velocity = self.velocity;
+        let (position, velocity) =
+            integrator.integrate(self.position, self.velocity, dt, |position, velocity| {
+                self.acceleration(params, position, velocity)
+            });
+        self.position = position;
+        self.velocity = constrain_velocity(params, old_velocity, velocity, dt);
+    }
+
+    /// One directional perception of `other` by the separation, alignement and cohesion rules.
+    pub fn perceive(&mut self, params: &FlockParams, world: &World<Vec3>, other: &Self) {
+        let to_other = world.offset(self.position, other.position);
+        let neighbourhood = &mut self.neighbourhood;
+        neighbourhood.perceive_separation(params, self.velocity, to_other);
+        neighbourhood.perceive_alignement(params, self.velocity, to_other, other.velocity);
+        neighbourhood.perceive_cohesion(params, self.velocity, self.position, to_other);
+    }
+
+    /// Half of the displacement pushing the boid out of `other` when they overlap,
+    /// leaving both untouched.
+    pub fn collision_push(&self, world: &World<Vec3>, other: &Self) -> Vec3 {
+        let away = world.offset(other.position, self.position);
+        collision_push(away, self.size + other.size)
+    }
+}
@@ -0,0 +1,310 @@
+//! Storage of a 2D flock as a struct of arrays, one per field of [`Boid`].
+//!
+//! The neighbour pass reads the positions and velocities of thousands of boids per step,
+//! which stay contiguous in memory instead of being spread between colder fields.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    boid::{Boid, BoidState, Steering},
+    math::{Vec2, Vec3},
+};
+
+/// The boids of a [`crate::Simulation`], boid `i` being the `i`th item of every array.
+/// Serialized as a list of [`Boid`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(from = "Vec<Boid>", into = "Vec<Boid>")]
+pub struct Boids {
+    pub(crate) positions: Vec<Vec2>,
+    pub(crate) velocities: Vec<Vec2>,
+    pub(crate) previous_positions: Vec<Vec2>,
+    pub(crate) sizes: Vec<f32>,
+    pub(crate) species: Vec<usize>,
+    pub(crate) waypoints: Vec<usize>,
+    pub(crate) colors: Vec<Vec3>,
+    pub(crate) avg_colors: Vec<Vec3>,
+    pub(crate) steering: Vec<Steering>,
+}
+
+impl Boids {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    pub fn push(&mut self, boid: Boid) {
+        let Boid {
+            position,
+            velocity,
+            previous_position,
+            size,
+            species,
+            waypoint,
+            color,
+            avg_color,
+            steering,
+        } = boid;
+        self.positions.push(position);
+        self.velocities.push(velocity);
+        self.previous_positions.push(previous_position);
+        self.sizes.push(size);
+        self.species.push(species);
+        self.waypoints.push(waypoint);
+        self.colors.push(color);
+        self.avg_colors.push(avg_color);
+        self.steering.push(steering);
+    }
+
+    pub fn get(&self, index: usize) -> Option<BoidRef<'_>> {
+        (index < self.len()).then_some(BoidRef { boids: self, index })
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = BoidRef<'_>> + ExactSizeIterator + Clone {
+        (0..self.len()).map(|index| BoidRef { boids: self, index })
+    }
+
+    pub fn positions(&self) -> &[Vec2] {
+        &self.positions
+    }
+
+    pub fn velocities(&self) -> &[Vec2] {
+        &self.velocities
+    }
+
+    pub fn previous_positions(&self) -> &[Vec2] {
+        &self.previous_positions
+    }
+
+    pub fn sizes(&self) -> &[f32] {
+        &self.sizes
+    }
+
+    pub fn species(&self) -> &[usize] {
+        &self.species
+    }
+
+    pub fn avg_colors(&self) -> &[Vec3] {
+        &self.avg_colors
+    }
+
+    /// What the steering rules read of boid `i`.
+    pub(crate) fn state(&self, i: usize) -> BoidState {
+        BoidState {
+            position: self.positions[i],
+            velocity: self.velocities[i],
+            size: self.sizes[i],
+            avg_color: self.avg_colors[i],
+        }
+    }
+
+    /// Run `update` on every boid, on all the cores when `parallel` is set.
+    pub(crate) fn for_each_mut(
+        &mut self,
+        parallel: bool,
+        update: impl Fn(BoidMut<'_>) + Sync + Send,
+    ) {
+        let Self {
+            positions,
+            velocities,
+            previous_positions,
+            sizes,
+            species,
+            waypoints,
+            colors,
+            avg_colors,
+            steering,
+        } = self;
+
+        #[cfg(feature = "parallel")]
+        if parallel {
+            use rayon::prelude::*;
+
+            (
+                positions.par_iter_mut(),
+                velocities.par_iter_mut(),
+                previous_positions.par_iter_mut(),
+                sizes.par_iter(),
+                species.par_iter(),
+                waypoints.par_iter_mut(),
+                colors.par_iter(),
+                avg_colors.par_iter_mut(),
+                steering.par_iter_mut(),
+            )
+                .into_par_iter()
+                .for_each(
+                    |(
+                        position,
+                        velocity,
+                        previous_position,
+                        &size,
+                        &species,
+                        waypoint,
+                        &color,
+                        avg_color,
+                        steering,
+                    )| {
+                        update(BoidMut {
+                            position,
+                            velocity,
+                            previous_position,
+                            size,
+                            species,
+                            waypoint,
+                            color,
+                            avg_color,
+                            steering,
+                        })
+                    },
+                );
+            return;
+        }
+        #[cfg(not(feature = "parallel"))]
+        debug_assert!(!parallel);
+
+        for i in 0..positions.len() {
+            update(BoidMut {
+                position: &mut positions[i],
+                velocity: &mut velocities[i],
+                previous_position: &mut previous_positions[i],
+                size: sizes[i],
+                species: species[i],
+                waypoint: &mut waypoints[i],
+                color: colors[i],
+                avg_color: &mut avg_colors[i],
+                steering: &mut steering[i],
+            });
+        }
+    }
+}
+
+impl From<Vec<Boid>> for Boids {
+    fn from(boids: Vec<Boid>) -> Self {
+        boids.into_iter().collect()
+    }
+}
+
+impl FromIterator<Boid> for Boids {
+    fn from_iter<I: IntoIterator<Item = Boid>>(iter: I) -> Self {
+        let mut boids = Self::new();
+        for boid in iter {
+            boids.push(boid);
+        }
+        boids
+    }
+}
+
+impl From<Boids> for Vec<Boid> {
+    fn from(boids: Boids) -> Self {
+        boids.iter().map(|boid| boid.to_boid()).collect()
+    }
+}
+
+/// View of one boid of [`Boids`], for rendering and tooling.
+#[derive(Clone, Copy)]
+pub struct BoidRef<'a> {
+    boids: &'a Boids,
+    index: usize,
+}
+
+impl<'a> BoidRef<'a> {
+    /// Index of the boid in the flock.
+    pub fn index(self) -> usize {
+        self.index
+    }
+
+    pub fn position(self) -> Vec2 {
+        self.boids.positions[self.index]
+    }
+
+    pub fn velocity(self) -> Vec2 {
+        self.boids.velocities[self.index]
+    }
+
+    /// Position before the last physics step, used to interpolate rendering.
+    pub fn previous_position(self) -> Vec2 {
+        self.boids.previous_positions[self.index]
+    }
+
+    /// Position between the previous and the current physics step, `alpha` in `[0, 1]`.
+    pub fn interpolated_position(self, alpha: f32) -> Vec2 {
+        let previous = self.previous_position();
+        previous + (self.position() - previous) * alpha
+    }
+
+    pub fn size(self) -> f32 {
+        self.boids.sizes[self.index]
+    }
+
+    /// Index of the species of the boid in the simulation.
+    pub fn species(self) -> usize {
+        self.boids.species[self.index]
+    }
+
+    /// Number of waypoints of the route already reached.
+    pub fn waypoint(self) -> usize {
+        self.boids.waypoints[self.index]
+    }
+
+    /// Own color, as derived from the boid id unless set otherwise.
+    pub fn color(self) -> &'a Vec3 {
+        &self.boids.colors[self.index]
+    }
+
+    /// Color blended with the neighbours, the one that should be displayed.
+    pub fn avg_color(self) -> &'a Vec3 {
+        &self.boids.avg_colors[self.index]
+    }
+
+    /// Number of neighbours the boid flocked with during the last step.
+    pub fn neighbour_count(self) -> u32 {
+        self.boids.steering[self.index].neighbour_count()
+    }
+
+    pub fn to_boid(self) -> Boid {
+        let boids = self.boids;
+        let i = self.index;
+        Boid {
+            position: boids.positions[i],
+            velocity: boids.velocities[i],
+            previous_position: boids.previous_positions[i],
+            size: boids.sizes[i],
+            species: boids.species[i],
+            waypoint: boids.waypoints[i],
+            color: boids.colors[i],
+            avg_color: boids.avg_colors[i],
+            steering: boids.steering[i].clone(),
+        }
+    }
+}
+
+/// Mutable view of one boid of [`Boids`] during a pass of a step,
+/// the fields no pass changes being copied.
+pub(crate) struct BoidMut<'a> {
+    pub position: &'a mut Vec2,
+    pub velocity: &'a mut Vec2,
+    pub previous_position: &'a mut Vec2,
+    pub size: f32,
+    pub species: usize,
+    pub waypoint: &'a mut usize,
+    pub color: Vec3,
+    pub avg_color: &'a mut Vec3,
+    pub steering: &'a mut Steering,
+}
+
+impl BoidMut<'_> {
+    pub fn state(&self) -> BoidState {
+        BoidState {
+            position: *self.position,
+            velocity: *self.velocity,
+            size: self.size,
+            avg_color: *self.avg_color,
+        }
+    }
+}
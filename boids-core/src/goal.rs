@@ -0,0 +1,104 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::math::Vec2;
+
+/// Point the boids seek, or flee from when `weight` is negative.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Attractor {
+    pub position: Vec2,
+    #[serde(default = "default_weight")]
+    pub weight: f32,
+    /// Distance beyond which the boids ignore it, unbounded when absent.
+    #[serde(default)]
+    pub radius: Option<f32>,
+}
+
+fn default_weight() -> f32 {
+    1.
+}
+
+impl Attractor {
+    pub fn new(position: impl Into<Vec2>, weight: f32, radius: Option<f32>) -> Self {
+        Self {
+            position: position.into(),
+            weight,
+            radius,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), GoalError> {
+        if !self.position.is_finite() || !self.weight.is_finite() {
+            return Err(GoalError::NotFinite);
+        }
+        match self.radius {
+            Some(radius) if !radius.is_finite() => Err(GoalError::NotFinite),
+            Some(radius) if radius < 0. => Err(GoalError::NegativeRadius { radius }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Whether a boid at squared distance `dist_sq` feels it.
+    pub fn reaches(&self, dist_sq: f32) -> bool {
+        self.radius.is_none_or(|radius| dist_sq <= radius * radius)
+    }
+}
+
+/// Ordered waypoints, each boid moving on to the next one
+/// once closer than `FlockParams::waypoint_radius` to its current one.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Route {
+    pub waypoints: Vec<Vec2>,
+    /// Go back to the first waypoint after the last one, instead of staying around it.
+    #[serde(default)]
+    pub looping: bool,
+}
+
+impl Route {
+    /// A route without waypoints, looping or not, leads nowhere and is rejected.
+    pub fn validate(&self) -> Result<(), GoalError> {
+        if self.waypoints.is_empty() {
+            return Err(GoalError::EmptyRoute);
+        }
+        if !self.waypoints.iter().all(|waypoint| waypoint.is_finite()) {
+            return Err(GoalError::NotFinite);
+        }
+        Ok(())
+    }
+
+    /// Waypoint to seek when the `index` first ones have been reached.
+    pub fn target(&self, index: usize) -> Option<Vec2> {
+        let len = self.waypoints.len();
+        if len == 0 {
+            return None;
+        }
+        let index = if self.looping {
+            index % len
+        } else {
+            index.min(len - 1)
+        };
+        Some(self.waypoints[index])
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GoalError {
+    NotFinite,
+    NegativeRadius { radius: f32 },
+    EmptyRoute,
+}
+
+impl fmt::Display for GoalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GoalError::NotFinite => write!(f, "attractor and waypoint coordinates must be finite"),
+            GoalError::NegativeRadius { radius } => {
+                write!(f, "attractor radius must be positive, got {radius}")
+            }
+            GoalError::EmptyRoute => write!(f, "a route needs at least one waypoint"),
+        }
+    }
+}
+
+impl std::error::Error for GoalError {}
@@ -0,0 +1,196 @@
+use crate::{
+    math::Vec2,
+    quadtree::Aabb,
+    spatial::{SpatialIndex, distance_sq_to_any, offer},
+};
+
+/// Uniform grid of square cells, each listing the points inside it.
+/// Rebuilding only clears the cells, which keep their allocations.
+pub struct UniformGrid {
+    bound: Aabb,
+    /// Wanted size of the cells, they grow past it when there would be too many of them.
+    min_cell_size: f32,
+    cell_size: f32,
+    columns: usize,
+    rows: usize,
+    cells: Vec<Vec<(Vec2, usize)>>,
+}
+
+impl UniformGrid {
+    /// Upper bound on the number of cells, reached when a point strays far from the others.
+    pub const MAX_CELLS: usize = 1 << 22;
+
+    pub fn new(bound: Aabb, cell_size: f32) -> Self {
+        let mut grid = Self {
+            bound,
+            min_cell_size: cell_size.max(f32::EPSILON),
+            cell_size: 0.,
+            columns: 0,
+            rows: 0,
+            cells: Vec::new(),
+        };
+        grid.resize(bound);
+        grid
+    }
+
+    pub fn bound(&self) -> Aabb {
+        self.bound
+    }
+
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    /// Clear the grid and cover `bound` with cells.
+    fn resize(&mut self, bound: Aabb) {
+        let extent = bound.max - bound.min;
+        let fitting = (extent[0] * extent[1] / Self::MAX_CELLS as f32).sqrt();
+        self.cell_size = self.min_cell_size.max(fitting);
+        self.bound = bound;
+        self.columns = ((extent[0] / self.cell_size).ceil() as usize).max(1);
+        self.rows = ((extent[1] / self.cell_size).ceil() as usize).max(1);
+        for cell in &mut self.cells {
+            cell.clear();
+        }
+        self.cells.resize_with(self.columns * self.rows, Vec::new);
+    }
+
+    /// Column and row of the cell `pos` is in, possibly outside of the grid.
+    fn cell_coords(&self, pos: Vec2) -> (isize, isize) {
+        let local = (pos - self.bound.min) / self.cell_size;
+        (local[0].floor() as isize, local[1].floor() as isize)
+    }
+
+    /// Index in `cells` of the cell at `(column, row)`, none outside of the grid.
+    fn cell_index(&self, (column, row): (isize, isize)) -> Option<usize> {
+        let inside =
+            (0..self.columns as isize).contains(&column) && (0..self.rows as isize).contains(&row);
+        inside.then(|| row as usize * self.columns + column as usize)
+    }
+
+    /// Index in `cells` of the cell `pos` is in, the last row and column including their
+    /// far edge.
+    fn cell_of(&self, pos: Vec2) -> Option<usize> {
+        if !self.bound.contains(pos) {
+            return None;
+        }
+        let (column, row) = self.cell_coords(pos);
+        let column = column.min(self.columns as isize - 1);
+        let row = row.min(self.rows as isize - 1);
+        self.cell_index((column, row))
+    }
+
+    /// Call `visit` on every cell at a Chebyshev distance of exactly `ring` cells
+    /// from `center`.
+    fn visit_ring(&self, center: (isize, isize), ring: isize, mut visit: impl FnMut(usize)) {
+        let (column, row) = center;
+        for r in row - ring..=row + ring {
+            let on_edge = r == row - ring || r == row + ring;
+            let step = if on_edge || ring == 0 {
+                1
+            } else {
+                (2 * ring) as usize
+            };
+            for c in (column - ring..=column + ring).step_by(step) {
+                if let Some(cell) = self.cell_index((c, r)) {
+                    visit(cell);
+                }
+            }
+        }
+    }
+}
+
+impl SpatialIndex for UniformGrid {
+    fn rebuild(&mut self, bound: Aabb, positions: &[Vec2]) {
+        let bound = positions.iter().copied().fold(bound, Aabb::expanded_to);
+        self.resize(bound);
+        for (i, &pos) in positions.iter().enumerate() {
+            self.insert(pos, i);
+        }
+    }
+
+    fn insert(&mut self, pos: Vec2, index: usize) -> bool {
+        match self.cell_of(pos) {
+            Some(cell) => {
+                self.cells[cell].push((pos, index));
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn remove(&mut self, pos: Vec2, index: usize) -> bool {
+        let Some(cell) = self.cell_of(pos) else {
+            return false;
+        };
+        let cell = &mut self.cells[cell];
+        match cell.iter().position(|&point| point == (pos, index)) {
+            Some(i) => {
+                cell.swap_remove(i);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn query_range(&self, range: &Aabb, out: &mut Vec<usize>) {
+        if !self.bound.intersects(range) {
+            return;
+        }
+
+        let (min_column, min_row) = self.cell_coords(range.min);
+        let (max_column, max_row) = self.cell_coords(range.max);
+        let columns = min_column.max(0)..=max_column.min(self.columns as isize - 1);
+        for row in min_row.max(0)..=max_row.min(self.rows as isize - 1) {
+            for column in columns.clone() {
+                let cell = row as usize * self.columns + column as usize;
+                out.extend(
+                    self.cells[cell]
+                        .iter()
+                        .filter(|(pos, _)| range.contains(*pos))
+                        .map(|(_, index)| *index),
+                );
+            }
+        }
+    }
+
+    /// Search rings of cells of growing size around each image, until no point
+    /// outside of them can be nearer than the `k` found.
+    fn k_nearest_to_any(&self, images: &[Vec2], k: usize, out: &mut Vec<usize>) {
+        if k == 0 {
+            return;
+        }
+        let centers: Vec<_> = images
+            .iter()
+            .map(|&image| self.cell_coords(image))
+            .collect();
+        // past this ring, every cell of the grid has been visited from every image
+        let last_ring = centers
+            .iter()
+            .map(|&(column, row)| {
+                let columns = column.abs().max(self.columns as isize - 1 - column);
+                let rows = row.abs().max(self.rows as isize - 1 - row);
+                columns.max(rows)
+            })
+            .max()
+            .unwrap_or(0);
+
+        let mut best = Vec::with_capacity(k + 1);
+        for ring in 0..=last_ring {
+            for &center in &centers {
+                self.visit_ring(center, ring, |cell| {
+                    for &(pos, index) in &self.cells[cell] {
+                        offer(&mut best, k, distance_sq_to_any(images, pos), index);
+                    }
+                });
+            }
+
+            // every point within `ring` cells of an image has been seen
+            let reach = ring as f32 * self.cell_size;
+            if best.len() == k && best[k - 1].0 < reach * reach {
+                break;
+            }
+        }
+        out.extend(best.into_iter().map(|(_, index)| index));
+    }
+}
@@ -0,0 +1,68 @@
+use crate::{
+    scenario::{Scenario, ScenarioError},
+    simulation::Simulation,
+    simulation3::Simulation3,
+};
+
+pub struct HeadlessParams {
+    pub scenario: Scenario,
+    pub steps: u32,
+}
+
+impl Default for HeadlessParams {
+    fn default() -> Self {
+        Self {
+            scenario: Scenario::default(),
+            steps: 1000,
+        }
+    }
+}
+
+/// Build a simulation from `params.scenario` and run `params.steps` physics steps
+/// at the scenario fixed dt, without any window or GL context.
+pub fn run(params: &HeadlessParams) -> Result<Simulation, ScenarioError> {
+    run_with(params, |_, _| ())
+}
+
+/// Same as [`run`], calling `on_step` with the step number after each step.
+pub fn run_with(
+    params: &HeadlessParams,
+    on_step: impl FnMut(u32, &Simulation),
+) -> Result<Simulation, ScenarioError> {
+    let sim = params.scenario.build()?;
+    Ok(step_with(sim, params.steps, on_step))
+}
+
+/// Run `steps` more physics steps of `sim`, such as one loaded from a snapshot,
+/// calling `on_step` with the step number, counted from 1, after each step.
+pub fn step_with(
+    mut sim: Simulation,
+    steps: u32,
+    mut on_step: impl FnMut(u32, &Simulation),
+) -> Simulation {
+    for step in 1..=steps {
+        sim.step();
+        on_step(step, &sim);
+    }
+    sim
+}
+
+/// Same as [`run`] with the 3D flock of [`Scenario::build_3d`].
+pub fn run_3d(params: &HeadlessParams) -> Result<Simulation3, ScenarioError> {
+    run_with_3d(params, |_, _| ())
+}
+
+/// Same as [`run_with`] with the 3D flock of [`Scenario::build_3d`].
+pub fn run_with_3d(
+    params: &HeadlessParams,
+    mut on_step: impl FnMut(u32, &Simulation3),
+) -> Result<Simulation3, ScenarioError> {
+    let mut sim = params.scenario.build_3d()?;
+
+    for step in 1..=params.steps {
+        sim.step();
+        on_step(step, &sim);
+    }
+
+    Ok(sim)
+}
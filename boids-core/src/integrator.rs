@@ -0,0 +1,110 @@
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::math::Vector;
+
+/// Numerical scheme used to move the boids from their steering acceleration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Integrator {
+    /// `x += v dt` then `v += a dt`, cheap but gains energy.
+    ExplicitEuler,
+    /// `v += a dt` then `x += v dt`, symplectic and as cheap as explicit Euler.
+    #[default]
+    SemiImplicitEuler,
+    /// Velocity Verlet, the velocity at the end of the step being predicted
+    /// to evaluate velocity dependent accelerations.
+    Verlet,
+    /// Classic fourth order Runge-Kutta, four acceleration evaluations per step.
+    Rk4,
+}
+
+impl Integrator {
+    pub const ALL: [Integrator; 4] = [
+        Integrator::ExplicitEuler,
+        Integrator::SemiImplicitEuler,
+        Integrator::Verlet,
+        Integrator::Rk4,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Integrator::ExplicitEuler => "explicit_euler",
+            Integrator::SemiImplicitEuler => "semi_implicit_euler",
+            Integrator::Verlet => "verlet",
+            Integrator::Rk4 => "rk4",
+        }
+    }
+
+    /// Advance `(position, velocity)` by `dt` under `acceleration(position, velocity)`.
+    pub fn integrate<V: Vector>(
+        self,
+        position: V,
+        velocity: V,
+        dt: f32,
+        acceleration: impl Fn(V, V) -> V,
+    ) -> (V, V) {
+        match self {
+            Integrator::ExplicitEuler => {
+                let a = acceleration(position, velocity);
+                (position + velocity * dt, velocity + a * dt)
+            }
+            Integrator::SemiImplicitEuler => {
+                let velocity = velocity + acceleration(position, velocity) * dt;
+                (position + velocity * dt, velocity)
+            }
+            Integrator::Verlet => {
+                let a0 = acceleration(position, velocity);
+                let new_position = position + velocity * dt + a0 * (0.5 * dt * dt);
+                let predicted_velocity = velocity + a0 * dt;
+                let a1 = acceleration(new_position, predicted_velocity);
+                (new_position, velocity + (a0 + a1) * (0.5 * dt))
+            }
+            Integrator::Rk4 => {
+                let half_dt = 0.5 * dt;
+
+                let (k1_x, k1_v) = (velocity, acceleration(position, velocity));
+
+                let (x2, v2) = (position + k1_x * half_dt, velocity + k1_v * half_dt);
+                let (k2_x, k2_v) = (v2, acceleration(x2, v2));
+
+                let (x3, v3) = (position + k2_x * half_dt, velocity + k2_v * half_dt);
+                let (k3_x, k3_v) = (v3, acceleration(x3, v3));
+
+                let (x4, v4) = (position + k3_x * dt, velocity + k3_v * dt);
+                let (k4_x, k4_v) = (v4, acceleration(x4, v4));
+
+                let sixth_dt = dt / 6.;
+                (
+                    position + (k1_x + (k2_x + k3_x) * 2. + k4_x) * sixth_dt,
+                    velocity + (k1_v + (k2_v + k3_v) * 2. + k4_v) * sixth_dt,
+                )
+            }
+        }
+    }
+}
+
+impl fmt::Display for Integrator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Integrator {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.replace('-', "_");
+        Self::ALL
+            .into_iter()
+            .find(|integrator| integrator.name() == s)
+            .ok_or_else(|| {
+                let names: Vec<_> = Self::ALL.iter().map(|i| i.name()).collect();
+                format!(
+                    "unknown integrator `{s}`, expected one of {}",
+                    names.join(", ")
+                )
+            })
+    }
+}
@@ -0,0 +1,40 @@
+pub mod boid;
+pub mod boid3;
+pub mod boids;
+pub mod goal;
+pub mod grid;
+pub mod headless;
+pub mod integrator;
+pub mod math;
+pub mod obstacle;
+pub mod octree;
+mod parallel;
+pub mod params;
+pub mod predator;
+pub mod quadtree;
+pub mod recorder;
+pub mod replay;
+pub mod rng;
+pub mod scenario;
+pub mod simulation;
+pub mod simulation3;
+pub mod snapshot;
+pub mod spatial;
+pub mod species;
+pub mod steering;
+pub mod timestep;
+pub mod world;
+
+pub use boid::Boid;
+pub use boid3::Boid3;
+pub use boids::{BoidRef, Boids};
+pub use goal::{Attractor, Route};
+pub use math::{Vec2, Vec3, Vector};
+pub use obstacle::Obstacle;
+pub use params::{FlockParams, NeighbourMode};
+pub use predator::Predator;
+pub use scenario::Scenario;
+pub use simulation::Simulation;
+pub use simulation3::Simulation3;
+pub use species::{Interaction, InteractionMatrix, Species};
+pub use world::{Boundary, World};
@@ -0,0 +1,371 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::Debug,
+    ops::{Add, AddAssign, Div, DivAssign, Index, IndexMut, Mul, MulAssign, Neg, Sub, SubAssign},
+};
+
+/// What the steering rules need from a vector, implemented by [`Vec2`] and [`Vec3`].
+pub trait Vector:
+    Debug
+    + Copy
+    + Default
+    + PartialEq
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<f32, Output = Self>
+    + Div<f32, Output = Self>
+    + Neg<Output = Self>
+    + AddAssign
+    + SubAssign
+    + MulAssign<f32>
+    + DivAssign<f32>
+    + Index<usize, Output = f32>
+    + IndexMut<usize>
+{
+    const DIM: usize;
+
+    /// Every component set to `value`.
+    fn splat(value: f32) -> Self;
+    fn dot(self, other: Self) -> f32;
+    fn length_sq(self) -> f32;
+    fn length(self) -> f32;
+    fn normalized(self) -> Self;
+    fn truncated(self, max: f32) -> Self;
+
+    /// Unsigned angle between the two vectors, in `[0, pi]`.
+    fn angle_to(self, other: Self) -> f32;
+    /// Rotated by `angle` radians toward `other`, in the plane they span.
+    fn rotated_toward(self, other: Self, angle: f32) -> Self;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Vec2(pub [f32; 2]);
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Vec3(pub [f32; 3]);
+
+macro_rules! impl_vector {
+    ($name:ident, $n:literal) => {
+        impl $name {
+            pub const ZERO: Self = Self([0.; $n]);
+
+            #[inline]
+            pub fn zero() -> Self {
+                Self::ZERO
+            }
+
+            #[inline]
+            pub fn as_array(&self) -> &[f32; $n] {
+                &self.0
+            }
+
+            #[inline]
+            pub fn as_mut_array(&mut self) -> &mut [f32; $n] {
+                &mut self.0
+            }
+
+            #[inline]
+            pub fn dot(self, other: Self) -> f32 {
+                let mut result = 0.;
+                for i in 0..$n {
+                    result += self.0[i] * other.0[i];
+                }
+                result
+            }
+
+            #[inline]
+            pub fn length_sq(self) -> f32 {
+                self.dot(self)
+            }
+
+            #[inline]
+            pub fn length(self) -> f32 {
+                self.length_sq().sqrt()
+            }
+
+            #[inline]
+            pub fn distance_sq(self, other: Self) -> f32 {
+                (self - other).length_sq()
+            }
+
+            #[inline]
+            pub fn distance(self, other: Self) -> f32 {
+                (self - other).length()
+            }
+
+            #[inline]
+            pub fn is_finite(self) -> bool {
+                self.0.iter().all(|x| x.is_finite())
+            }
+
+            #[inline]
+            pub fn normalized(self) -> Self {
+                self / self.length()
+            }
+
+            /// Scaled down to a norm of `max` when longer.
+            #[inline]
+            pub fn truncated(self, max: f32) -> Self {
+                let length_sq = self.length_sq();
+                if length_sq > max * max {
+                    self * (max / length_sq.sqrt())
+                } else {
+                    self
+                }
+            }
+        }
+
+        impl Vector for $name {
+            const DIM: usize = $n;
+
+            #[inline]
+            fn splat(value: f32) -> Self {
+                Self([value; $n])
+            }
+
+            #[inline]
+            fn dot(self, other: Self) -> f32 {
+                $name::dot(self, other)
+            }
+
+            #[inline]
+            fn length_sq(self) -> f32 {
+                $name::length_sq(self)
+            }
+
+            #[inline]
+            fn length(self) -> f32 {
+                $name::length(self)
+            }
+
+            #[inline]
+            fn normalized(self) -> Self {
+                $name::normalized(self)
+            }
+
+            #[inline]
+            fn truncated(self, max: f32) -> Self {
+                $name::truncated(self, max)
+            }
+
+            #[inline]
+            fn angle_to(self, other: Self) -> f32 {
+                $name::angle_to(self, other)
+            }
+
+            #[inline]
+            fn rotated_toward(self, other: Self, angle: f32) -> Self {
+                $name::rotated_toward(self, other, angle)
+            }
+        }
+
+        impl From<[f32; $n]> for $name {
+            #[inline]
+            fn from(value: [f32; $n]) -> Self {
+                Self(value)
+            }
+        }
+
+        impl From<$name> for [f32; $n] {
+            #[inline]
+            fn from(value: $name) -> Self {
+                value.0
+            }
+        }
+
+        impl Index<usize> for $name {
+            type Output = f32;
+
+            #[inline]
+            fn index(&self, index: usize) -> &f32 {
+                &self.0[index]
+            }
+        }
+
+        impl IndexMut<usize> for $name {
+            #[inline]
+            fn index_mut(&mut self, index: usize) -> &mut f32 {
+                &mut self.0[index]
+            }
+        }
+
+        impl Add for $name {
+            type Output = Self;
+
+            #[inline]
+            fn add(mut self, rhs: Self) -> Self {
+                self += rhs;
+                self
+            }
+        }
+
+        impl Sub for $name {
+            type Output = Self;
+
+            #[inline]
+            fn sub(mut self, rhs: Self) -> Self {
+                self -= rhs;
+                self
+            }
+        }
+
+        impl Mul<f32> for $name {
+            type Output = Self;
+
+            #[inline]
+            fn mul(mut self, rhs: f32) -> Self {
+                self *= rhs;
+                self
+            }
+        }
+
+        impl Div<f32> for $name {
+            type Output = Self;
+
+            #[inline]
+            fn div(mut self, rhs: f32) -> Self {
+                self /= rhs;
+                self
+            }
+        }
+
+        impl Neg for $name {
+            type Output = Self;
+
+            #[inline]
+            fn neg(self) -> Self {
+                self * -1.
+            }
+        }
+
+        impl AddAssign for $name {
+            #[inline]
+            fn add_assign(&mut self, rhs: Self) {
+                for i in 0..$n {
+                    self.0[i] += rhs.0[i];
+                }
+            }
+        }
+
+        impl SubAssign for $name {
+            #[inline]
+            fn sub_assign(&mut self, rhs: Self) {
+                for i in 0..$n {
+                    self.0[i] -= rhs.0[i];
+                }
+            }
+        }
+
+        impl MulAssign<f32> for $name {
+            #[inline]
+            fn mul_assign(&mut self, rhs: f32) {
+                for i in 0..$n {
+                    self.0[i] *= rhs;
+                }
+            }
+        }
+
+        impl DivAssign<f32> for $name {
+            #[inline]
+            fn div_assign(&mut self, rhs: f32) {
+                for i in 0..$n {
+                    self.0[i] /= rhs;
+                }
+            }
+        }
+    };
+}
+
+impl_vector!(Vec2, 2);
+impl_vector!(Vec3, 3);
+
+impl Vec2 {
+    #[inline]
+    pub fn new(x: f32, y: f32) -> Self {
+        Self([x, y])
+    }
+
+    #[inline]
+    pub fn x(&self) -> f32 {
+        self.0[0]
+    }
+
+    #[inline]
+    pub fn y(&self) -> f32 {
+        self.0[1]
+    }
+
+    /// Z component of the cross product of the two vectors extended to 3D.
+    #[inline]
+    pub fn perp_dot(self, other: Self) -> f32 {
+        self[0] * other[1] - self[1] * other[0]
+    }
+
+    /// Counter-clockwise rotation by `angle` radians.
+    #[inline]
+    pub fn rotated(self, angle: f32) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        Self([self[0] * cos - self[1] * sin, self[0] * sin + self[1] * cos])
+    }
+
+    #[inline]
+    pub fn angle_to(self, other: Self) -> f32 {
+        self.perp_dot(other).atan2(self.dot(other)).abs()
+    }
+
+    #[inline]
+    pub fn rotated_toward(self, other: Self, angle: f32) -> Self {
+        self.rotated(angle.copysign(self.perp_dot(other)))
+    }
+}
+
+impl From<(f32, f32)> for Vec2 {
+    #[inline]
+    fn from((x, y): (f32, f32)) -> Self {
+        Self([x, y])
+    }
+}
+
+impl Vec3 {
+    #[inline]
+    pub fn new(x: f32, y: f32, z: f32) -> Self {
+        Self([x, y, z])
+    }
+
+    #[inline]
+    pub fn cross(self, other: Self) -> Self {
+        Self([
+            self[1] * other[2] - self[2] * other[1],
+            self[2] * other[0] - self[0] * other[2],
+            self[0] * other[1] - self[1] * other[0],
+        ])
+    }
+
+    #[inline]
+    pub fn angle_to(self, other: Self) -> f32 {
+        self.cross(other).length().atan2(self.dot(other))
+    }
+
+    /// Rodrigues rotation around the normal of the plane spanned with `other`,
+    /// any normal when they are parallel.
+    pub fn rotated_toward(self, other: Self, angle: f32) -> Self {
+        let mut axis = self.cross(other);
+        if axis.length_sq() <= 0. {
+            // any direction orthogonal to self
+            let helper = if self[0].abs() < self[1].abs().max(self[2].abs()) {
+                Vec3::new(1., 0., 0.)
+            } else {
+                Vec3::new(0., 1., 0.)
+            };
+            axis = self.cross(helper);
+            if axis.length_sq() <= 0. {
+                return self;
+            }
+        }
+        let axis = axis.normalized();
+        let (sin, cos) = angle.sin_cos();
+        self * cos + axis.cross(self) * sin + axis * (axis.dot(self) * (1. - cos))
+    }
+}
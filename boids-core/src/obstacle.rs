@@ -0,0 +1,228 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{math::Vec2, quadtree::Aabb};
+
+/// Static piece of the environment boids steer around and cannot go through.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Obstacle {
+    Circle {
+        center: Vec2,
+        radius: f32,
+    },
+    /// Axis aligned box.
+    Box {
+        min: Vec2,
+        max: Vec2,
+    },
+    /// Simple polygon, the last point being linked back to the first one.
+    Polygon {
+        points: Vec<Vec2>,
+    },
+}
+
+impl Obstacle {
+    pub fn circle(center: impl Into<Vec2>, radius: f32) -> Self {
+        Obstacle::Circle {
+            center: center.into(),
+            radius,
+        }
+    }
+
+    pub fn square(center: impl Into<Vec2>, half_dim: f32) -> Self {
+        let Aabb { min, max } = Aabb::from_center(center.into(), half_dim);
+        Obstacle::Box { min, max }
+    }
+
+    /// Regular polygon with `sides` vertices on the circle of radius `radius`.
+    pub fn regular_polygon(center: impl Into<Vec2>, radius: f32, sides: usize) -> Self {
+        let center = center.into();
+        let points = (0..sides)
+            .map(|i| {
+                let angle = i as f32 * std::f32::consts::TAU / sides as f32;
+                center + Vec2::new(angle.cos(), angle.sin()) * radius
+            })
+            .collect();
+        Obstacle::Polygon { points }
+    }
+
+    /// Checks the shape is one [`Obstacle::signed_distance`] is defined for.
+    pub fn validate(&self) -> Result<(), ObstacleError> {
+        match self {
+            Obstacle::Circle { center, radius } => {
+                if !center.is_finite() || !radius.is_finite() {
+                    return Err(ObstacleError::NotFinite);
+                }
+                if *radius < 0. {
+                    return Err(ObstacleError::NegativeRadius { radius: *radius });
+                }
+            }
+            Obstacle::Box { min, max } => {
+                if !min.is_finite() || !max.is_finite() {
+                    return Err(ObstacleError::NotFinite);
+                }
+                if min[0] > max[0] || min[1] > max[1] {
+                    return Err(ObstacleError::InvertedBox {
+                        min: *min,
+                        max: *max,
+                    });
+                }
+            }
+            Obstacle::Polygon { points } => {
+                if points.len() < 3 {
+                    return Err(ObstacleError::TooFewPoints {
+                        count: points.len(),
+                    });
+                }
+                if !points.iter().all(|point| point.is_finite()) {
+                    return Err(ObstacleError::NotFinite);
+                }
+                // the last point is linked back to the first one
+                for (i, &a) in points.iter().enumerate() {
+                    if a == points[(i + 1) % points.len()] {
+                        return Err(ObstacleError::DegenerateEdge { index: i });
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn bounding_box(&self) -> Aabb {
+        match self {
+            Obstacle::Circle { center, radius } => Aabb::from_center(*center, *radius),
+            Obstacle::Box { min, max } => Aabb::from_min_max(*min, *max),
+            Obstacle::Polygon { points } => {
+                let first = points.first().copied().unwrap_or_default();
+                points
+                    .iter()
+                    .fold(Aabb::from_min_max(first, first), |bound, &point| {
+                        bound.expanded_to(point)
+                    })
+            }
+        }
+    }
+
+    /// Signed distance from `point` to the boundary, negative inside,
+    /// and the outward normal of the boundary at the closest point.
+    pub fn signed_distance(&self, point: Vec2) -> (f32, Vec2) {
+        match self {
+            Obstacle::Circle { center, radius } => {
+                let offset = point - *center;
+                let dist = offset.length();
+                let normal = if dist > 0. {
+                    offset / dist
+                } else {
+                    Vec2::new(1., 0.)
+                };
+                (dist - radius, normal)
+            }
+            Obstacle::Box { min, max } => box_signed_distance(*min, *max, point),
+            Obstacle::Polygon { points } => polygon_signed_distance(points, point),
+        }
+    }
+}
+
+fn box_signed_distance(min: Vec2, max: Vec2, point: Vec2) -> (f32, Vec2) {
+    let closest = Vec2::new(
+        point[0].clamp(min[0], max[0]),
+        point[1].clamp(min[1], max[1]),
+    );
+    let offset = point - closest;
+    let dist = offset.length();
+    if dist > 0. {
+        return (dist, offset / dist);
+    }
+
+    // inside : get out through the nearest side
+    let sides = [
+        (point[0] - min[0], Vec2::new(-1., 0.)),
+        (max[0] - point[0], Vec2::new(1., 0.)),
+        (point[1] - min[1], Vec2::new(0., -1.)),
+        (max[1] - point[1], Vec2::new(0., 1.)),
+    ];
+    let (depth, normal) = sides
+        .into_iter()
+        .min_by(|a, b| a.0.total_cmp(&b.0))
+        .unwrap_or((0., Vec2::new(1., 0.)));
+    (-depth, normal)
+}
+
+fn polygon_signed_distance(points: &[Vec2], point: Vec2) -> (f32, Vec2) {
+    let mut best = (f32::INFINITY, Vec2::new(1., 0.));
+    let mut inside = false;
+
+    for (i, &a) in points.iter().enumerate() {
+        let b = points[(i + 1) % points.len()];
+        let edge = b - a;
+
+        let t = if edge.length_sq() > 0. {
+            ((point - a).dot(edge) / edge.length_sq()).clamp(0., 1.)
+        } else {
+            0.
+        };
+        let offset = point - (a + edge * t);
+        let dist = offset.length();
+        if dist < best.0 {
+            let normal = if dist > 0. {
+                offset / dist
+            } else {
+                Vec2::new(edge[1], -edge[0]).normalized()
+            };
+            best = (dist, normal);
+        }
+
+        // even-odd rule
+        if (a[1] > point[1]) != (b[1] > point[1])
+            && point[0] < a[0] + (point[1] - a[1]) / (b[1] - a[1]) * edge[0]
+        {
+            inside = !inside;
+        }
+    }
+
+    if inside { (-best.0, -best.1) } else { best }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ObstacleError {
+    NotFinite,
+    NegativeRadius {
+        radius: f32,
+    },
+    InvertedBox {
+        min: Vec2,
+        max: Vec2,
+    },
+    TooFewPoints {
+        count: usize,
+    },
+    /// The edge from the point `index` to the next one has a length of zero.
+    DegenerateEdge {
+        index: usize,
+    },
+}
+
+impl fmt::Display for ObstacleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ObstacleError::NotFinite => write!(f, "obstacle coordinates must be finite"),
+            ObstacleError::NegativeRadius { radius } => {
+                write!(f, "obstacle radius must be positive, got {radius}")
+            }
+            ObstacleError::InvertedBox { min, max } => {
+                write!(f, "obstacle box min {min:?} must not be above max {max:?}")
+            }
+            ObstacleError::TooFewPoints { count } => {
+                write!(f, "obstacle polygon needs at least 3 points, got {count}")
+            }
+            ObstacleError::DegenerateEdge { index } => write!(
+                f,
+                "obstacle polygon point {index} is the same as the next one"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ObstacleError {}
@@ -0,0 +1,271 @@
+use crate::{math::Vec3, spatial::NeighbourQuery};
+
+/// Axis aligned box, the 3D counterpart of [`crate::quadtree::Aabb`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb3 {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl Aabb3 {
+    pub fn from_min_max(min: Vec3, max: Vec3) -> Self {
+        Self { min, max }
+    }
+
+    pub fn from_center(center: Vec3, half_dim: f32) -> Self {
+        let half = Vec3::new(half_dim, half_dim, half_dim);
+        Self {
+            min: center - half,
+            max: center + half,
+        }
+    }
+
+    #[inline]
+    pub fn contains(&self, point: Vec3) -> bool {
+        (0..3).all(|i| point[i] >= self.min[i] && point[i] <= self.max[i])
+    }
+
+    #[inline]
+    pub fn intersects(&self, other: &Self) -> bool {
+        (0..3).all(|i| self.min[i] <= other.max[i] && self.max[i] >= other.min[i])
+    }
+
+    /// Squared distance from `point` to the box, 0 inside.
+    pub fn distance_sq_to(&self, point: Vec3) -> f32 {
+        let mut dist_sq = 0.;
+        for i in 0..3 {
+            let d = (self.min[i] - point[i]).max(point[i] - self.max[i]).max(0.);
+            dist_sq += d * d;
+        }
+        dist_sq
+    }
+
+    pub fn expanded_to(mut self, point: Vec3) -> Self {
+        for i in 0..3 {
+            self.min[i] = self.min[i].min(point[i]);
+            self.max[i] = self.max[i].max(point[i]);
+        }
+        self
+    }
+
+    fn octants(&self) -> [Aabb3; 8] {
+        let center = (self.min + self.max) * 0.5;
+        std::array::from_fn(|octant| {
+            let mut min = self.min;
+            let mut max = center;
+            for i in 0..3 {
+                if octant & (1 << i) != 0 {
+                    min[i] = center[i];
+                    max[i] = self.max[i];
+                }
+            }
+            Aabb3::from_min_max(min, max)
+        })
+    }
+}
+
+/// Point octree storing indices into an external slice, the 3D counterpart of
+/// [`crate::quadtree::Quadtree`].
+pub struct Octree {
+    bound: Aabb3,
+    points: Vec<(Vec3, usize)>,
+    children: Option<Box<[Octree; 8]>>,
+    depth: u8,
+}
+
+impl Octree {
+    pub const CAPACITY: usize = 10;
+    const MAX_DEPTH: u8 = 16;
+
+    pub fn new(bound: Aabb3) -> Self {
+        Self::with_depth(bound, 0)
+    }
+
+    fn with_depth(bound: Aabb3, depth: u8) -> Self {
+        Self {
+            bound,
+            points: Vec::with_capacity(Self::CAPACITY),
+            children: None,
+            depth,
+        }
+    }
+
+    /// Build a tree over `positions`, index `i` referring to the `i`th position.
+    /// The root bound grows to contain every position.
+    pub fn build(bound: Aabb3, positions: impl Iterator<Item = Vec3> + Clone) -> Self {
+        let bound = positions.clone().fold(bound, Aabb3::expanded_to);
+        let mut tree = Self::new(bound);
+        for (i, pos) in positions.enumerate() {
+            tree.insert(pos, i);
+        }
+        tree
+    }
+
+    pub fn bound(&self) -> Aabb3 {
+        self.bound
+    }
+
+    pub fn insert(&mut self, pos: Vec3, index: usize) -> bool {
+        if !self.bound.contains(pos) {
+            return false;
+        }
+
+        if let Some(children) = &mut self.children {
+            return children.iter_mut().any(|child| child.insert(pos, index));
+        }
+
+        if self.points.len() < Self::CAPACITY || self.depth >= Self::MAX_DEPTH {
+            self.points.push((pos, index));
+            return true;
+        }
+
+        self.subdivide();
+        self.insert(pos, index)
+    }
+
+    fn subdivide(&mut self) {
+        let depth = self.depth + 1;
+        let mut children = Box::new(self.bound.octants().map(|o| Self::with_depth(o, depth)));
+
+        for (pos, index) in self.points.drain(..) {
+            let _ = children.iter_mut().any(|child| child.insert(pos, index));
+        }
+        self.children = Some(children);
+    }
+
+    /// Push into `out` the index of every point inside `range`.
+    pub fn query_range(&self, range: &Aabb3, out: &mut Vec<usize>) {
+        if !self.bound.intersects(range) {
+            return;
+        }
+
+        out.extend(
+            self.points
+                .iter()
+                .filter(|(pos, _)| range.contains(*pos))
+                .map(|(_, index)| *index),
+        );
+
+        if let Some(children) = &self.children {
+            for child in children.iter() {
+                child.query_range(range, out);
+            }
+        }
+    }
+
+    /// Same as [`Octree::query_range`] in a world repeating itself every `period`
+    /// from the origin, `range` reaching across the faces.
+    /// The indices pushed are sorted and unique.
+    pub fn query_range_wrapped(&self, range: &Aabb3, period: Vec3, out: &mut Vec<usize>) {
+        let start = out.len();
+        let world = Aabb3::from_min_max(Vec3::zero(), period);
+        for image in 0..27 {
+            let mut shift = Vec3::zero();
+            let mut code = image;
+            for i in 0..3 {
+                shift[i] = ((code % 3) as f32 - 1.) * period[i];
+                code /= 3;
+            }
+            let image = Aabb3::from_min_max(range.min + shift, range.max + shift);
+            if image.intersects(&world) {
+                self.query_range(&image, out);
+            }
+        }
+        // a range wider than the world meets some points more than once
+        let mut found = out.split_off(start);
+        found.sort_unstable();
+        found.dedup();
+        out.append(&mut found);
+    }
+
+    /// Push into `out` the index of the `k` points nearest to `point`, nearest first,
+    /// ties being broken by index.
+    pub fn k_nearest(&self, point: Vec3, k: usize, out: &mut Vec<usize>) {
+        let mut best = Vec::with_capacity(k + 1);
+        self.k_nearest_rec(&[point], k, &mut best);
+        out.extend(best.into_iter().map(|(_, index)| index));
+    }
+
+    /// Same as [`Octree::k_nearest`] in a world repeating itself every `period`
+    /// from the origin, `point` being inside that world.
+    pub fn k_nearest_wrapped(&self, point: Vec3, k: usize, period: Vec3, out: &mut Vec<usize>) {
+        // the nearest copy of any point is toward the closest faces
+        let mut shift = Vec3::zero();
+        for i in 0..3 {
+            shift[i] = if point[i] < period[i] * 0.5 {
+                period[i]
+            } else {
+                -period[i]
+            };
+        }
+        let images: [Vec3; 8] = std::array::from_fn(|corner| {
+            let mut image = point;
+            for i in 0..3 {
+                if corner & (1 << i) != 0 {
+                    image[i] += shift[i];
+                }
+            }
+            image
+        });
+
+        let mut best = Vec::with_capacity(k + 1);
+        self.k_nearest_rec(&images, k, &mut best);
+        out.extend(best.into_iter().map(|(_, index)| index));
+    }
+
+    /// Distances are taken to the nearest of the `images` of the query point.
+    fn k_nearest_rec(&self, images: &[Vec3], k: usize, best: &mut Vec<(f32, usize)>) {
+        let bound_dist_sq = |bound: &Aabb3| {
+            images
+                .iter()
+                .map(|&image| bound.distance_sq_to(image))
+                .fold(f32::INFINITY, f32::min)
+        };
+        if k == 0 || (best.len() == k && bound_dist_sq(&self.bound) > best[k - 1].0) {
+            return;
+        }
+
+        for &(pos, index) in &self.points {
+            let dist_sq = images
+                .iter()
+                .map(|&image| pos.distance_sq(image))
+                .fold(f32::INFINITY, f32::min);
+            let rank = best.partition_point(|&(best_dist_sq, best_index)| {
+                best_dist_sq < dist_sq || (best_dist_sq == dist_sq && best_index < index)
+            });
+            if rank < k {
+                best.insert(rank, (dist_sq, index));
+                best.truncate(k);
+            }
+        }
+
+        if let Some(children) = &self.children {
+            let mut order: [usize; 8] = std::array::from_fn(|i| i);
+            order.sort_by(|&a, &b| {
+                let dist_a = bound_dist_sq(&children[a].bound);
+                let dist_b = bound_dist_sq(&children[b].bound);
+                dist_a.total_cmp(&dist_b)
+            });
+            for child in order {
+                children[child].k_nearest_rec(images, k, best);
+            }
+        }
+    }
+}
+
+impl NeighbourQuery<Vec3> for Octree {
+    fn query_around(&self, center: Vec3, radius: f32, period: Option<Vec3>, out: &mut Vec<usize>) {
+        let range = Aabb3::from_center(center, radius);
+        match period {
+            Some(period) => self.query_range_wrapped(&range, period, out),
+            None => self.query_range(&range, out),
+        }
+    }
+
+    fn nearest(&self, point: Vec3, k: usize, period: Option<Vec3>, out: &mut Vec<usize>) {
+        match period {
+            Some(period) => self.k_nearest_wrapped(point, k, period, out),
+            None => self.k_nearest(point, k, out),
+        }
+    }
+}
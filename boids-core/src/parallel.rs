@@ -0,0 +1,99 @@
+//! Passes of a step over all the boids, run on every core with the `parallel` feature.
+//!
+//! Each boid only reads the state at the start of the step, so the parallel passes give
+//! the same result, bit for bit, as the serial ones.
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::math::Vector;
+
+/// Whether a new simulation runs its steps in parallel, which it does with the feature.
+pub(crate) fn enabled_by_default() -> bool {
+    cfg!(feature = "parallel")
+}
+
+/// Run `update` on every item, on all the cores when `parallel` is set.
+pub(crate) fn for_each<T: Send>(
+    items: &mut [T],
+    parallel: bool,
+    update: impl Fn(&mut T) + Sync + Send,
+) {
+    #[cfg(feature = "parallel")]
+    if parallel {
+        items.par_iter_mut().for_each(update);
+        return;
+    }
+    #[cfg(not(feature = "parallel"))]
+    debug_assert!(!parallel);
+    items.iter_mut().for_each(update);
+}
+
+/// Write to `next` every item of `current` after `update`, which gets the index of the item
+/// and a scratch buffer for its neighbours.
+///
+/// In parallel, the items are handed to the threads in the order of `order`, so that each
+/// thread works on a compact region of the world and reads neighbours close in memory.
+pub(crate) fn update_into<T: Clone + Send + Sync>(
+    current: &[T],
+    next: &mut Vec<T>,
+    parallel: bool,
+    order: impl FnOnce() -> Vec<usize>,
+    update: impl Fn(usize, &mut T, &mut Vec<usize>) + Sync + Send,
+) {
+    #[cfg(feature = "parallel")]
+    if parallel {
+        let mut updated: Vec<(usize, T)> = order()
+            .into_par_iter()
+            .map_init(Vec::new, |neighbours, i| {
+                let mut item = current[i].clone();
+                update(i, &mut item, neighbours);
+                (i, item)
+            })
+            .collect();
+
+        // back to the index order, moving the items
+        updated.par_sort_unstable_by_key(|&(i, _)| i);
+        next.clear();
+        next.par_extend(updated.into_par_iter().map(|(_, item)| item));
+        return;
+    }
+    #[cfg(not(feature = "parallel"))]
+    debug_assert!(!parallel);
+    // the order only matters to split the work between threads
+    let _ = order;
+
+    current.clone_into(next);
+    let mut neighbours = Vec::new();
+    for (i, item) in next.iter_mut().enumerate() {
+        update(i, item, &mut neighbours);
+    }
+}
+
+/// Indices of `positions` sorted along a Z-order curve over the box `min..max`,
+/// nearby positions being mostly next to each other.
+pub(crate) fn spatial_order<V: Vector>(
+    positions: impl ExactSizeIterator<Item = V>,
+    min: V,
+    max: V,
+) -> Vec<usize> {
+    const BITS: usize = 10;
+    let cells = (1 << BITS) as f32;
+
+    let mut keyed: Vec<(u32, usize)> = positions
+        .enumerate()
+        .map(|(i, position)| {
+            let mut code = 0;
+            for axis in 0..V::DIM {
+                let t = (position[axis] - min[axis]) / (max[axis] - min[axis]);
+                let cell = (t * cells).clamp(0., cells - 1.) as u32;
+                for bit in 0..BITS {
+                    code |= ((cell >> bit) & 1) << (bit * V::DIM + axis);
+                }
+            }
+            (code, i)
+        })
+        .collect();
+    keyed.sort_unstable();
+    keyed.into_iter().map(|(_, i)| i).collect()
+}
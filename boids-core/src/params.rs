@@ -0,0 +1,214 @@
+use std::{f32::consts::TAU, fmt};
+
+use serde::{Deserialize, Serialize};
+
+/// Steering parameters shared by every boid of a flock.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FlockParams {
+    pub border_margin: f32,
+    pub separation: f32,
+    pub alignement: f32,
+    pub cohesion: f32,
+    pub neighbours: NeighbourMode,
+
+    /// Full angle of the perception cone of each rule, in radians,
+    /// `TAU` meaning no blind spot.
+    pub separation_fov: f32,
+    pub alignement_fov: f32,
+    pub cohesion_fov: f32,
+
+    pub avoid_factor: f32,
+    pub matching_factor: f32,
+    pub centering_factor: f32,
+    pub turn_factor: f32,
+
+    /// Distance under which boids flee from predators.
+    pub flee_radius: f32,
+    pub flee_factor: f32,
+
+    /// Distance to an obstacle under which boids steer away from it.
+    pub obstacle_margin: f32,
+    pub obstacle_factor: f32,
+
+    /// Gain of the seek steering toward the attractors and the route.
+    pub seek_factor: f32,
+    /// Distance to a target under which boids slow down to arrive on it.
+    pub arrive_radius: f32,
+    /// Distance under which a waypoint counts as reached.
+    pub waypoint_radius: f32,
+
+    pub min_speed: f32,
+    pub max_speed: f32,
+    /// Bound on the norm of the steering acceleration.
+    pub max_force: f32,
+    /// Bound on the angular velocity of the heading, in radians per second.
+    pub max_turn_rate: Option<f32>,
+}
+
+impl Default for FlockParams {
+    fn default() -> Self {
+        Self {
+            border_margin: 50.,
+            separation: 8.,
+            alignement: 40.,
+            cohesion: 40.,
+            neighbours: NeighbourMode::Metric,
+
+            separation_fov: TAU,
+            alignement_fov: TAU,
+            cohesion_fov: TAU,
+
+            avoid_factor: 3.,
+            matching_factor: 3.,
+            centering_factor: 0.03,
+            turn_factor: 2.,
+
+            flee_radius: 80.,
+            flee_factor: 800.,
+
+            obstacle_margin: 30.,
+            obstacle_factor: 1500.,
+
+            seek_factor: 1.,
+            arrive_radius: 100.,
+            waypoint_radius: 40.,
+
+            min_speed: 50.,
+            max_speed: 300.,
+            max_force: 1000.,
+            max_turn_rate: None,
+        }
+    }
+}
+
+/// How the neighbours taken into account by the alignement and cohesion rules are chosen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum NeighbourMode {
+    /// Every boid within the radius of the rule.
+    #[default]
+    Metric,
+    /// The `k` nearest boids whatever their distance, separation keeping its radius.
+    Topological { k: usize },
+}
+
+impl NeighbourMode {
+    /// Upper bound on `k` in topological mode.
+    pub const MAX_K: usize = 1024;
+}
+
+impl FlockParams {
+    /// Radius containing every neighbour a steering rule may consider in metric mode.
+    pub fn perception_radius(&self) -> f32 {
+        self.separation.max(self.alignement).max(self.cohesion)
+    }
+
+    /// Whether a neighbour at `distance_sq` is within a rule of squared radius `radius_sq`,
+    /// always true in topological mode.
+    #[inline]
+    pub fn reaches(&self, distance_sq: f32, radius_sq: f32) -> bool {
+        match self.neighbours {
+            NeighbourMode::Metric => distance_sq < radius_sq,
+            NeighbourMode::Topological { .. } => true,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), ParamsError> {
+        let fields = [
+            ("border_margin", self.border_margin),
+            ("separation", self.separation),
+            ("alignement", self.alignement),
+            ("cohesion", self.cohesion),
+            ("separation_fov", self.separation_fov),
+            ("alignement_fov", self.alignement_fov),
+            ("cohesion_fov", self.cohesion_fov),
+            ("avoid_factor", self.avoid_factor),
+            ("matching_factor", self.matching_factor),
+            ("centering_factor", self.centering_factor),
+            ("turn_factor", self.turn_factor),
+            ("flee_radius", self.flee_radius),
+            ("flee_factor", self.flee_factor),
+            ("obstacle_margin", self.obstacle_margin),
+            ("obstacle_factor", self.obstacle_factor),
+            ("seek_factor", self.seek_factor),
+            ("arrive_radius", self.arrive_radius),
+            ("waypoint_radius", self.waypoint_radius),
+            ("min_speed", self.min_speed),
+            ("max_speed", self.max_speed),
+            ("max_force", self.max_force),
+            ("max_turn_rate", self.max_turn_rate.unwrap_or(0.)),
+        ];
+
+        for (name, value) in fields {
+            if !value.is_finite() {
+                return Err(ParamsError::NotFinite { name });
+            }
+            if value < 0. {
+                return Err(ParamsError::Negative { name, value });
+            }
+        }
+
+        for (name, value) in [
+            ("separation_fov", self.separation_fov),
+            ("alignement_fov", self.alignement_fov),
+            ("cohesion_fov", self.cohesion_fov),
+        ] {
+            if value > TAU {
+                return Err(ParamsError::AngleRange { name, value });
+            }
+        }
+
+        if let NeighbourMode::Topological { k } = self.neighbours
+            && !(1..=NeighbourMode::MAX_K).contains(&k)
+        {
+            return Err(ParamsError::NeighbourCount { k });
+        }
+
+        if self.max_speed < self.min_speed {
+            return Err(ParamsError::SpeedRange {
+                min_speed: self.min_speed,
+                max_speed: self.max_speed,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParamsError {
+    NotFinite { name: &'static str },
+    Negative { name: &'static str, value: f32 },
+    SpeedRange { min_speed: f32, max_speed: f32 },
+    AngleRange { name: &'static str, value: f32 },
+    NeighbourCount { k: usize },
+}
+
+impl fmt::Display for ParamsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParamsError::NotFinite { name } => write!(f, "parameter `{name}` must be finite"),
+            ParamsError::Negative { name, value } => {
+                write!(f, "parameter `{name}` must be positive, got {value}")
+            }
+            ParamsError::AngleRange { name, value } => {
+                write!(f, "parameter `{name}` must be at most 2 pi, got {value}")
+            }
+            ParamsError::NeighbourCount { k } => write!(
+                f,
+                "the number of neighbours must be between 1 and {}, got {k}",
+                NeighbourMode::MAX_K
+            ),
+            ParamsError::SpeedRange {
+                min_speed,
+                max_speed,
+            } => write!(
+                f,
+                "max_speed ({max_speed}) must not be lower than min_speed ({min_speed})"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParamsError {}
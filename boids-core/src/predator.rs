@@ -0,0 +1,112 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    math::Vec2,
+    params::ParamsError,
+    steering::{bounce, limit_turn},
+    world::World,
+};
+
+/// Limits of the predators, deliberately different from the boid ones :
+/// faster but turning slower, so that a flock can dodge them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PredatorParams {
+    pub size: f32,
+    pub max_speed: f32,
+    pub max_force: f32,
+    /// Bound on the angular velocity of the heading, in radians per second.
+    pub max_turn_rate: Option<f32>,
+    /// Gain of the seek steering toward the prey.
+    pub chase_factor: f32,
+}
+
+impl Default for PredatorParams {
+    fn default() -> Self {
+        Self {
+            size: 5.,
+            max_speed: 320.,
+            max_force: 600.,
+            max_turn_rate: Some(3.),
+            chase_factor: 4.,
+        }
+    }
+}
+
+impl PredatorParams {
+    pub fn validate(&self) -> Result<(), ParamsError> {
+        let fields = [
+            ("size", self.size),
+            ("max_speed", self.max_speed),
+            ("max_force", self.max_force),
+            ("max_turn_rate", self.max_turn_rate.unwrap_or(0.)),
+            ("chase_factor", self.chase_factor),
+        ];
+        for (name, value) in fields {
+            if !value.is_finite() {
+                return Err(ParamsError::NotFinite { name });
+            }
+            if value < 0. {
+                return Err(ParamsError::Negative { name, value });
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Predator {
+    pub position: Vec2,
+    pub velocity: Vec2,
+    /// Position before the last physics step, used to interpolate rendering.
+    pub previous_position: Vec2,
+}
+
+impl Predator {
+    pub fn new(pos: (f32, f32)) -> Self {
+        let pos = pos.into();
+        Self {
+            position: pos,
+            velocity: Vec2::zero(),
+            previous_position: pos,
+        }
+    }
+
+    pub fn interpolated_position(&self, alpha: f32) -> Vec2 {
+        self.previous_position + (self.position - self.previous_position) * alpha
+    }
+
+    /// Steer toward `prey` at full speed, or keep going when there is nothing to hunt.
+    pub fn chase(&mut self, params: &PredatorParams, world: &World, prey: Option<Vec2>, dt: f32) {
+        let old_velocity = self.velocity;
+
+        if let Some(prey) = prey {
+            let to_prey = world.offset(self.position, prey);
+            if to_prey.length_sq() > 0. {
+                let desired = to_prey.normalized() * params.max_speed;
+                let steering =
+                    ((desired - self.velocity) * params.chase_factor).truncated(params.max_force);
+                self.velocity += steering * dt;
+            }
+        }
+
+        if let Some(max_turn_rate) = params.max_turn_rate {
+            self.velocity = limit_turn(old_velocity, self.velocity, max_turn_rate * dt);
+        }
+        self.velocity = self.velocity.truncated(params.max_speed);
+
+        self.previous_position = self.position;
+        self.position += self.velocity * dt;
+    }
+
+    /// Keep the predator inside the world, bouncing on the edges unless the world wraps.
+    pub fn handle_border_colision(&mut self, params: &PredatorParams, world: &World) {
+        if world.wraps() {
+            let wrapped = world.wrap(self.position);
+            self.previous_position += wrapped - self.position;
+            self.position = wrapped;
+        } else {
+            bounce(&mut self.position, &mut self.velocity, params.size, world);
+        }
+    }
+}
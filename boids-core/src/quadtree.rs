@@ -0,0 +1,224 @@
+use crate::{
+    math::Vec2,
+    spatial::{SpatialIndex, distance_sq_to_any, offer},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub min: Vec2,
+    pub max: Vec2,
+}
+
+impl Aabb {
+    pub fn from_min_max(min: impl Into<Vec2>, max: impl Into<Vec2>) -> Self {
+        Self {
+            min: min.into(),
+            max: max.into(),
+        }
+    }
+
+    pub fn from_center(center: Vec2, half_dim: f32) -> Self {
+        let half = Vec2::new(half_dim, half_dim);
+        Self {
+            min: center - half,
+            max: center + half,
+        }
+    }
+
+    #[inline]
+    pub fn contains(&self, point: Vec2) -> bool {
+        point[0] >= self.min[0]
+            && point[0] <= self.max[0]
+            && point[1] >= self.min[1]
+            && point[1] <= self.max[1]
+    }
+
+    #[inline]
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.min[0] <= other.max[0]
+            && self.max[0] >= other.min[0]
+            && self.min[1] <= other.max[1]
+            && self.max[1] >= other.min[1]
+    }
+
+    /// Squared distance from `point` to the box, 0 inside.
+    pub fn distance_sq_to(&self, point: Vec2) -> f32 {
+        let mut dist_sq = 0.;
+        for i in 0..2 {
+            let d = (self.min[i] - point[i]).max(point[i] - self.max[i]).max(0.);
+            dist_sq += d * d;
+        }
+        dist_sq
+    }
+
+    pub fn expanded_to(mut self, point: Vec2) -> Self {
+        for i in 0..2 {
+            self.min[i] = self.min[i].min(point[i]);
+            self.max[i] = self.max[i].max(point[i]);
+        }
+        self
+    }
+
+    fn quadrants(&self) -> [Aabb; 4] {
+        let center = (self.min + self.max) * 0.5;
+        [
+            Aabb::from_min_max(self.min, center),
+            Aabb::from_min_max((center[0], self.min[1]), (self.max[0], center[1])),
+            Aabb::from_min_max((self.min[0], center[1]), (center[0], self.max[1])),
+            Aabb::from_min_max(center, self.max),
+        ]
+    }
+}
+
+/// Point quadtree storing indices into an external slice.
+pub struct Quadtree {
+    bound: Aabb,
+    points: Vec<(Vec2, usize)>,
+    children: Option<Box<[Quadtree; 4]>>,
+    depth: u8,
+}
+
+impl Quadtree {
+    pub const CAPACITY: usize = 10;
+    const MAX_DEPTH: u8 = 16;
+
+    pub fn new(bound: Aabb) -> Self {
+        Self::with_depth(bound, 0)
+    }
+
+    fn with_depth(bound: Aabb, depth: u8) -> Self {
+        Self {
+            bound,
+            points: Vec::with_capacity(Self::CAPACITY),
+            children: None,
+            depth,
+        }
+    }
+
+    /// Build a tree over `positions`, index `i` referring to the `i`th position.
+    /// The root bound grows to contain every position.
+    pub fn build(bound: Aabb, positions: impl Iterator<Item = Vec2> + Clone) -> Self {
+        let bound = positions.clone().fold(bound, Aabb::expanded_to);
+        let mut tree = Self::new(bound);
+        for (i, pos) in positions.enumerate() {
+            tree.insert(pos, i);
+        }
+        tree
+    }
+
+    pub fn bound(&self) -> Aabb {
+        self.bound
+    }
+
+    fn subdivide(&mut self) {
+        let depth = self.depth + 1;
+        let [a, b, c, d] = self.bound.quadrants();
+        let mut children = Box::new([
+            Self::with_depth(a, depth),
+            Self::with_depth(b, depth),
+            Self::with_depth(c, depth),
+            Self::with_depth(d, depth),
+        ]);
+
+        for (pos, index) in self.points.drain(..) {
+            let _ = children.iter_mut().any(|child| child.insert(pos, index));
+        }
+        self.children = Some(children);
+    }
+
+    /// Distances are taken to the nearest of the `images` of the query point.
+    fn k_nearest_rec(&self, images: &[Vec2], k: usize, best: &mut Vec<(f32, usize)>) {
+        let bound_dist_sq = |bound: &Aabb| {
+            images
+                .iter()
+                .map(|&image| bound.distance_sq_to(image))
+                .fold(f32::INFINITY, f32::min)
+        };
+        if k == 0 || (best.len() == k && bound_dist_sq(&self.bound) > best[k - 1].0) {
+            return;
+        }
+
+        for &(pos, index) in &self.points {
+            offer(best, k, distance_sq_to_any(images, pos), index);
+        }
+
+        if let Some(children) = &self.children {
+            let mut order = [0, 1, 2, 3];
+            order.sort_by(|&a, &b| {
+                let dist_a = bound_dist_sq(&children[a].bound);
+                let dist_b = bound_dist_sq(&children[b].bound);
+                dist_a.total_cmp(&dist_b)
+            });
+            for child in order {
+                children[child].k_nearest_rec(images, k, best);
+            }
+        }
+    }
+}
+
+impl SpatialIndex for Quadtree {
+    fn rebuild(&mut self, bound: Aabb, positions: &[Vec2]) {
+        *self = Self::build(bound, positions.iter().copied());
+    }
+
+    fn insert(&mut self, pos: Vec2, index: usize) -> bool {
+        if !self.bound.contains(pos) {
+            return false;
+        }
+
+        if let Some(children) = &mut self.children {
+            return children.iter_mut().any(|child| child.insert(pos, index));
+        }
+
+        if self.points.len() < Self::CAPACITY || self.depth >= Self::MAX_DEPTH {
+            self.points.push((pos, index));
+            return true;
+        }
+
+        self.subdivide();
+        self.insert(pos, index)
+    }
+
+    fn remove(&mut self, pos: Vec2, index: usize) -> bool {
+        if !self.bound.contains(pos) {
+            return false;
+        }
+
+        if let Some(children) = &mut self.children {
+            return children.iter_mut().any(|child| child.remove(pos, index));
+        }
+
+        match self.points.iter().position(|&point| point == (pos, index)) {
+            Some(i) => {
+                self.points.swap_remove(i);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn query_range(&self, range: &Aabb, out: &mut Vec<usize>) {
+        if !self.bound.intersects(range) {
+            return;
+        }
+
+        out.extend(
+            self.points
+                .iter()
+                .filter(|(pos, _)| range.contains(*pos))
+                .map(|(_, index)| *index),
+        );
+
+        if let Some(children) = &self.children {
+            for child in children.iter() {
+                child.query_range(range, out);
+            }
+        }
+    }
+
+    fn k_nearest_to_any(&self, images: &[Vec2], k: usize, out: &mut Vec<usize>) {
+        let mut best = Vec::with_capacity(k + 1);
+        self.k_nearest_rec(images, k, &mut best);
+        out.extend(best.into_iter().map(|(_, index)| index));
+    }
+}
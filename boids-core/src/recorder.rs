@@ -0,0 +1,288 @@
+//! Trajectories written to disk every few steps by a background thread,
+//! as CSV or as a compact binary columnar format.
+//!
+//! The binary format is little endian : the magic `BOIDSREC`, the `u32` format version,
+//! then one block per frame made of the step `u64`, the time `f64`, the boid count `u32`
+//! and the columns `x`, `y`, `vx`, `vy` as `f32` and `species`, `neighbours` as `u32`.
+
+use std::{
+    fmt,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+    str::FromStr,
+    sync::mpsc::{self, SyncSender, TrySendError},
+    thread::JoinHandle,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{math::Vec2, simulation::Simulation};
+
+pub const MAGIC: [u8; 8] = *b"BOIDSREC";
+/// Version of the binary format, bumped whenever its layout changes.
+pub const FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordFormat {
+    /// One line per boid and per frame.
+    Csv,
+    /// One block of columns per frame.
+    #[default]
+    Binary,
+}
+
+impl RecordFormat {
+    pub const ALL: [RecordFormat; 2] = [RecordFormat::Csv, RecordFormat::Binary];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            RecordFormat::Csv => "csv",
+            RecordFormat::Binary => "binary",
+        }
+    }
+
+    /// CSV for a `.csv` file, binary otherwise.
+    pub fn from_path(path: impl AsRef<Path>) -> Self {
+        match path.as_ref().extension().and_then(|ext| ext.to_str()) {
+            Some("csv") => RecordFormat::Csv,
+            _ => RecordFormat::Binary,
+        }
+    }
+
+    fn write_header(self, out: &mut impl Write) -> io::Result<()> {
+        match self {
+            RecordFormat::Csv => writeln!(out, "step,time,boid,x,y,vx,vy,species,neighbours"),
+            RecordFormat::Binary => {
+                out.write_all(&MAGIC)?;
+                out.write_all(&FORMAT_VERSION.to_le_bytes())
+            }
+        }
+    }
+
+    fn write_frame(self, frame: &Frame, out: &mut impl Write) -> io::Result<()> {
+        match self {
+            RecordFormat::Csv => {
+                for i in 0..frame.len() {
+                    let (pos, vel) = (frame.positions[i], frame.velocities[i]);
+                    writeln!(
+                        out,
+                        "{},{},{i},{},{},{},{},{},{}",
+                        frame.step,
+                        frame.time,
+                        pos[0],
+                        pos[1],
+                        vel[0],
+                        vel[1],
+                        frame.species[i],
+                        frame.neighbours[i]
+                    )?;
+                }
+                Ok(())
+            }
+            RecordFormat::Binary => {
+                out.write_all(&frame.step.to_le_bytes())?;
+                out.write_all(&frame.time.to_le_bytes())?;
+                out.write_all(&(frame.len() as u32).to_le_bytes())?;
+                for column in [&frame.positions, &frame.velocities] {
+                    for axis in 0..2 {
+                        for v in column {
+                            out.write_all(&v[axis].to_le_bytes())?;
+                        }
+                    }
+                }
+                for column in [&frame.species, &frame.neighbours] {
+                    for value in column {
+                        out.write_all(&value.to_le_bytes())?;
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl fmt::Display for RecordFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for RecordFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|format| format.name() == s)
+            .ok_or_else(|| format!("unknown record format `{s}`, expected csv or binary"))
+    }
+}
+
+/// State of the flock at one recorded step, one column per field.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Frame {
+    pub step: u64,
+    pub time: f64,
+    pub positions: Vec<Vec2>,
+    pub velocities: Vec<Vec2>,
+    pub species: Vec<u32>,
+    pub neighbours: Vec<u32>,
+}
+
+impl Frame {
+    pub fn capture(sim: &Simulation) -> Self {
+        let boids = sim.boids();
+        Self {
+            step: sim.step_count(),
+            time: sim.time(),
+            positions: boids.positions().to_vec(),
+            velocities: boids.velocities().to_vec(),
+            species: boids.species().iter().map(|&s| s as u32).collect(),
+            neighbours: boids.iter().map(|boid| boid.neighbour_count()).collect(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+}
+
+/// What [`Recorder::record`] does when the writer thread is [`Recorder::CAPACITY`] frames behind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WhenFull {
+    /// Wait for the writer, nothing being lost, for batch runs.
+    Block,
+    /// Drop the frame, so that a frame loop never stalls on the disk.
+    Skip,
+}
+
+/// Sends a [`Frame`] every `every` steps to a thread writing them to a file.
+pub struct Recorder {
+    every: u32,
+    when_full: WhenFull,
+    sender: Option<SyncSender<Frame>>,
+    writer: Option<JoinHandle<io::Result<()>>>,
+    path: String,
+
+    recorded: u64,
+    skipped: u64,
+}
+
+/// Frames written and dropped by a finished [`Recorder`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecordSummary {
+    pub recorded: u64,
+    pub skipped: u64,
+}
+
+impl Recorder {
+    /// Number of frames waiting to be written, at most.
+    pub const CAPACITY: usize = 32;
+
+    /// Create the file right away, so that a bad path is reported before the run.
+    pub fn create(
+        path: impl AsRef<Path>,
+        format: RecordFormat,
+        every: u32,
+        when_full: WhenFull,
+    ) -> Result<Self, RecordError> {
+        let path = path.as_ref().display().to_string();
+        let file = File::create(&path).map_err(|err| RecordError::Io(path.clone(), err))?;
+
+        let (sender, receiver) = mpsc::sync_channel::<Frame>(Self::CAPACITY);
+        let writer = std::thread::spawn(move || {
+            let mut out = BufWriter::new(file);
+            format.write_header(&mut out)?;
+            for frame in receiver {
+                format.write_frame(&frame, &mut out)?;
+            }
+            out.flush()
+        });
+
+        Ok(Self {
+            every: every.max(1),
+            when_full,
+            sender: Some(sender),
+            writer: Some(writer),
+            path,
+
+            recorded: 0,
+            skipped: 0,
+        })
+    }
+
+    /// Record `sim` if its step count is a multiple of `every`.
+    /// Does nothing once the writer stopped on an error, reported by [`Recorder::finish`].
+    pub fn record(&mut self, sim: &Simulation) {
+        if !sim.step_count().is_multiple_of(u64::from(self.every)) {
+            return;
+        }
+        let Some(sender) = &self.sender else {
+            return;
+        };
+
+        let frame = Frame::capture(sim);
+        let sent = match self.when_full {
+            WhenFull::Block => sender.send(frame).is_ok(),
+            WhenFull::Skip => match sender.try_send(frame) {
+                Ok(()) => true,
+                Err(TrySendError::Full(_)) => {
+                    self.skipped += 1;
+                    return;
+                }
+                Err(TrySendError::Disconnected(_)) => false,
+            },
+        };
+        if sent {
+            self.recorded += 1;
+        } else {
+            // the writer failed, finish tells why
+            self.sender = None;
+        }
+    }
+
+    /// Wait for every frame to be written and close the file.
+    /// Calling it again returns the same counts without doing anything.
+    pub fn finish(&mut self) -> Result<RecordSummary, RecordError> {
+        self.sender = None;
+        if let Some(writer) = self.writer.take() {
+            writer
+                .join()
+                .map_err(|_| RecordError::WriterPanicked)?
+                .map_err(|err| RecordError::Io(self.path.clone(), err))?;
+        }
+        Ok(RecordSummary {
+            recorded: self.recorded,
+            skipped: self.skipped,
+        })
+    }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        let _ = self.finish();
+    }
+}
+
+#[derive(Debug)]
+pub enum RecordError {
+    Io(String, io::Error),
+    WriterPanicked,
+}
+
+impl fmt::Display for RecordError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecordError::Io(path, err) => write!(f, "could not write recording {path} : {err}"),
+            RecordError::WriterPanicked => write!(f, "the recording writer thread panicked"),
+        }
+    }
+}
+
+impl std::error::Error for RecordError {}
@@ -0,0 +1,274 @@
+//! Playback of recorded trajectories, without running any physics.
+
+use std::{fmt, path::Path};
+
+use crate::{
+    math::Vec2,
+    recorder::{FORMAT_VERSION, Frame, MAGIC},
+};
+
+/// Recorded frames played back at a chosen speed, the shown frame being
+/// the last one recorded before the playback time.
+pub struct Replay {
+    frames: Vec<Frame>,
+    index: usize,
+    /// Recorded time being shown, in seconds.
+    time: f64,
+    speed: f32,
+    paused: bool,
+}
+
+impl Replay {
+    pub const MIN_SPEED: f32 = 1. / 64.;
+    pub const MAX_SPEED: f32 = 64.;
+
+    pub fn new(frames: Vec<Frame>) -> Result<Self, ReplayError> {
+        let Some(first) = frames.first() else {
+            return Err(ReplayError::Empty);
+        };
+        Ok(Self {
+            time: first.time,
+            frames,
+            index: 0,
+            speed: 1.,
+            paused: false,
+        })
+    }
+
+    /// Load a recording of [`crate::recorder::Recorder`], in either format.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ReplayError> {
+        let path = path.as_ref();
+        let bytes =
+            std::fs::read(path).map_err(|err| ReplayError::Io(path.display().to_string(), err))?;
+        let frames = if bytes.starts_with(&MAGIC) {
+            read_binary(&bytes)?
+        } else {
+            let s = std::str::from_utf8(&bytes)
+                .map_err(|_| ReplayError::Format("neither a binary nor a CSV recording".into()))?;
+            read_csv(s)?
+        };
+        Self::new(frames)
+    }
+
+    pub fn frames(&self) -> &[Frame] {
+        &self.frames
+    }
+
+    /// Frame being shown.
+    pub fn frame(&self) -> &Frame {
+        &self.frames[self.index]
+    }
+
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Never true, a replay having at least one frame.
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// How far the shown frame is in the recording, in `[0, 1]`.
+    pub fn progress(&self) -> f32 {
+        if self.frames.len() < 2 {
+            return 1.;
+        }
+        self.index as f32 / (self.frames.len() - 1) as f32
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Resuming at the last frame starts over from the first one.
+    pub fn set_paused(&mut self, paused: bool) {
+        if !paused && self.index + 1 == self.frames.len() {
+            self.show(0);
+        }
+        self.paused = paused;
+    }
+
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    /// Playback speed relative to the recorded time, clamped to
+    /// [`Replay::MIN_SPEED`] and [`Replay::MAX_SPEED`], left unchanged for a non finite `speed`.
+    pub fn set_speed(&mut self, speed: f32) {
+        if speed.is_finite() {
+            self.speed = speed.clamp(Self::MIN_SPEED, Self::MAX_SPEED);
+        }
+    }
+
+    /// Move the playback time forward by `frame_dt` times the speed, pausing on the last frame.
+    pub fn advance(&mut self, frame_dt: f32) {
+        if self.paused {
+            return;
+        }
+        self.time += f64::from(frame_dt.max(0.) * self.speed);
+        let after = self.frames.partition_point(|frame| frame.time <= self.time);
+        self.index = after.saturating_sub(1);
+        if after == self.frames.len() {
+            self.paused = true;
+        }
+    }
+
+    /// Pause and move by `delta` frames, backward when negative.
+    pub fn step_by(&mut self, delta: isize) {
+        self.paused = true;
+        let index = self.index.saturating_add_signed(delta);
+        self.show(index.min(self.frames.len() - 1));
+    }
+
+    /// Show the frame at `fraction` of the recording, in `[0, 1]`.
+    pub fn seek(&mut self, fraction: f32) {
+        let last = self.frames.len() - 1;
+        let index = (fraction.clamp(0., 1.) * last as f32).round() as usize;
+        self.show(index.min(last));
+    }
+
+    fn show(&mut self, index: usize) {
+        self.index = index;
+        self.time = self.frames[index].time;
+    }
+}
+
+/// Frames of the CSV format, the rows of a frame being consecutive and in boid order.
+fn read_csv(s: &str) -> Result<Vec<Frame>, ReplayError> {
+    let mut lines = s.lines().enumerate();
+    match lines.next() {
+        Some((_, header)) if header.trim() == "step,time,boid,x,y,vx,vy,species,neighbours" => (),
+        _ => return Err(ReplayError::Format("missing CSV header".into())),
+    }
+
+    let mut frames: Vec<Frame> = Vec::new();
+    for (number, line) in lines {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let invalid = || ReplayError::Format(format!("invalid CSV row on line {}", number + 1));
+        let fields: Vec<_> = line.split(',').map(str::trim).collect();
+        let [step, time, boid, x, y, vx, vy, species, neighbours] = fields[..] else {
+            return Err(invalid());
+        };
+        let step: u64 = step.parse().map_err(|_| invalid())?;
+        let float = |s: &str| s.parse::<f32>().map_err(|_| invalid());
+
+        if frames.last().is_none_or(|frame| frame.step != step) {
+            frames.push(Frame {
+                step,
+                time: time.parse().map_err(|_| invalid())?,
+                ..Frame::default()
+            });
+        }
+        let frame = frames.last_mut().unwrap();
+        if boid.parse::<usize>().map_err(|_| invalid())? != frame.len() {
+            return Err(invalid());
+        }
+        frame.positions.push(Vec2::new(float(x)?, float(y)?));
+        frame.velocities.push(Vec2::new(float(vx)?, float(vy)?));
+        frame.species.push(species.parse().map_err(|_| invalid())?);
+        frame
+            .neighbours
+            .push(neighbours.parse().map_err(|_| invalid())?);
+    }
+    Ok(frames)
+}
+
+/// Frames of the binary format described in [`crate::recorder`].
+fn read_binary(bytes: &[u8]) -> Result<Vec<Frame>, ReplayError> {
+    let mut input = Input { bytes };
+    input.take::<8>()?;
+    let version = u32::from_le_bytes(input.take()?);
+    if version != FORMAT_VERSION {
+        return Err(ReplayError::Version {
+            found: version,
+            expected: FORMAT_VERSION,
+        });
+    }
+
+    let mut frames = Vec::new();
+    while !input.bytes.is_empty() {
+        let step = u64::from_le_bytes(input.take()?);
+        let time = f64::from_le_bytes(input.take()?);
+        let len = u32::from_le_bytes(input.take()?) as usize;
+        // 4 f32 and 2 u32 per boid, checked before allocating the columns
+        if len
+            .checked_mul(24)
+            .is_none_or(|size| size > input.bytes.len())
+        {
+            return Err(ReplayError::Format("truncated binary recording".into()));
+        }
+
+        let mut columns = [(); 4].map(|_| Vec::with_capacity(len));
+        for column in &mut columns {
+            for _ in 0..len {
+                column.push(f32::from_le_bytes(input.take()?));
+            }
+        }
+        let [x, y, vx, vy] = columns;
+        let mut integers = [(); 2].map(|_| Vec::with_capacity(len));
+        for column in &mut integers {
+            for _ in 0..len {
+                column.push(u32::from_le_bytes(input.take()?));
+            }
+        }
+        let [species, neighbours] = integers;
+
+        frames.push(Frame {
+            step,
+            time,
+            positions: x.into_iter().zip(y).map(|(x, y)| Vec2::new(x, y)).collect(),
+            velocities: vx
+                .into_iter()
+                .zip(vy)
+                .map(|(x, y)| Vec2::new(x, y))
+                .collect(),
+            species,
+            neighbours,
+        });
+    }
+    Ok(frames)
+}
+
+struct Input<'a> {
+    bytes: &'a [u8],
+}
+
+impl Input<'_> {
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], ReplayError> {
+        let Some((head, tail)) = self.bytes.split_first_chunk::<N>() else {
+            return Err(ReplayError::Format("truncated binary recording".into()));
+        };
+        self.bytes = tail;
+        Ok(*head)
+    }
+}
+
+#[derive(Debug)]
+pub enum ReplayError {
+    Io(String, std::io::Error),
+    Format(String),
+    Version { found: u32, expected: u32 },
+    Empty,
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::Io(path, err) => write!(f, "could not read recording {path} : {err}"),
+            ReplayError::Format(reason) => write!(f, "invalid recording : {reason}"),
+            ReplayError::Version { found, expected } => write!(
+                f,
+                "recording of format version {found}, this build reads version {expected}"
+            ),
+            ReplayError::Empty => write!(f, "the recording has no frame"),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
@@ -0,0 +1,502 @@
+use std::{fmt, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    boid::Boid,
+    boid3::Boid3,
+    goal::{Attractor, GoalError, Route},
+    integrator::Integrator,
+    math::{Vec2, Vec3},
+    obstacle::{Obstacle, ObstacleError},
+    params::{FlockParams, ParamsError},
+    predator::PredatorParams,
+    rng::Rng,
+    simulation::{Simulation, spawn_spiral},
+    simulation3::Simulation3,
+    spatial::SpatialIndexKind,
+    species::{Interaction, InteractionMatrix, Species, SpeciesError},
+    timestep::FixedTimestep,
+    world::Boundary,
+};
+
+/// Everything needed to rebuild the same starting flock, loadable from a TOML or RON file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Scenario {
+    pub boid_count: usize,
+    pub world_size: (f32, f32),
+    /// Extent of the world along z, used by the 3D mode only.
+    pub depth: f32,
+    pub boundary: Boundary,
+    pub physics_hz: f32,
+    pub max_steps_per_frame: u32,
+    pub integrator: Integrator,
+    /// How the 2D mode finds neighbours, which does not change the trajectories.
+    pub spatial_index: SpatialIndexKind,
+    pub seed: u64,
+
+    pub spawn: SpawnDistribution,
+    pub initial_velocity: InitialVelocity,
+    pub params: FlockParams,
+
+    /// Predators spawned uniformly over the world.
+    pub predator_count: usize,
+    pub predator_params: PredatorParams,
+
+    pub obstacles: Vec<Obstacle>,
+    pub attractors: Vec<Attractor>,
+    pub route: Option<Route>,
+
+    /// When not empty, replaces `boid_count` and `params` by a population per species.
+    pub species: Vec<SpeciesSpawn>,
+    /// Row `a` column `b` is how species `a` reacts to species `b`,
+    /// every species flocking with every other when empty.
+    pub interactions: Vec<Vec<Interaction>>,
+}
+
+/// A species and how many boids of it to spawn.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpeciesSpawn {
+    #[serde(flatten)]
+    pub species: Species,
+    pub count: usize,
+}
+
+impl Default for Scenario {
+    fn default() -> Self {
+        Self {
+            boid_count: 10,
+            world_size: (800., 600.),
+            depth: 600.,
+            boundary: Boundary::default(),
+            physics_hz: FixedTimestep::DEFAULT_HZ,
+            max_steps_per_frame: FixedTimestep::DEFAULT_MAX_STEPS_PER_FRAME,
+            integrator: Integrator::default(),
+            spatial_index: SpatialIndexKind::default(),
+            seed: 0,
+
+            spawn: SpawnDistribution::Spiral,
+            initial_velocity: InitialVelocity::Zero,
+            params: FlockParams::default(),
+
+            predator_count: 0,
+            predator_params: PredatorParams::default(),
+
+            obstacles: Vec::new(),
+            attractors: Vec::new(),
+            route: None,
+
+            species: Vec::new(),
+            interactions: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SpawnDistribution {
+    /// Small spiral around the world center.
+    Spiral,
+    /// Uniform over the whole world.
+    Uniform,
+    /// Uniform inside a disc, centered on the world when `center` is not given.
+    Disc {
+        center: Option<(f32, f32)>,
+        radius: f32,
+    },
+    /// Square grid centered on the world.
+    Grid { spacing: f32 },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum InitialVelocity {
+    Zero,
+    Fixed {
+        velocity: (f32, f32),
+    },
+    /// Random heading with a speed uniform in `[min_speed, max_speed)`.
+    Random {
+        min_speed: f32,
+        max_speed: f32,
+    },
+}
+
+impl Scenario {
+    /// Load a scenario, the format being picked from the file extension (`.toml` or `.ron`).
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ScenarioError> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .map_err(|err| ScenarioError::Io(path.display().to_string(), err))?;
+
+        let scenario = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => Self::from_toml_str(&content)?,
+            Some("ron") => Self::from_ron_str(&content)?,
+            _ => return Err(ScenarioError::UnknownFormat(path.display().to_string())),
+        };
+
+        Ok(scenario)
+    }
+
+    pub fn from_toml_str(s: &str) -> Result<Self, ScenarioError> {
+        let scenario: Self = toml::from_str(s).map_err(ScenarioError::Toml)?;
+        scenario.validate()?;
+        Ok(scenario)
+    }
+
+    pub fn from_ron_str(s: &str) -> Result<Self, ScenarioError> {
+        let scenario: Self = ron::from_str(s).map_err(ScenarioError::Ron)?;
+        scenario.validate()?;
+        Ok(scenario)
+    }
+
+    pub fn validate(&self) -> Result<(), ScenarioError> {
+        let (w, h) = self.world_size;
+        if !(w > 0. && h > 0. && w.is_finite() && h.is_finite()) {
+            return Err(ScenarioError::Invalid(
+                "world_size must be finite and positive",
+            ));
+        }
+        if !(self.depth > 0. && self.depth.is_finite()) {
+            return Err(ScenarioError::Invalid("depth must be finite and positive"));
+        }
+        if !(self.physics_hz > 0. && self.physics_hz.is_finite()) {
+            return Err(ScenarioError::Invalid(
+                "physics_hz must be finite and positive",
+            ));
+        }
+        if self.max_steps_per_frame == 0 {
+            return Err(ScenarioError::Invalid(
+                "max_steps_per_frame must be at least 1",
+            ));
+        }
+        match self.spawn {
+            SpawnDistribution::Spiral | SpawnDistribution::Uniform => {}
+            SpawnDistribution::Disc { center, radius } => {
+                if center.is_some_and(|(x, y)| !(x.is_finite() && y.is_finite())) {
+                    return Err(ScenarioError::Invalid("spawn center must be finite"));
+                }
+                if !(radius >= 0. && radius.is_finite()) {
+                    return Err(ScenarioError::Invalid(
+                        "spawn radius must be finite and non negative",
+                    ));
+                }
+            }
+            SpawnDistribution::Grid { spacing } => {
+                if !(spacing > 0. && spacing.is_finite()) {
+                    return Err(ScenarioError::Invalid(
+                        "spawn spacing must be finite and positive",
+                    ));
+                }
+            }
+        }
+        match self.initial_velocity {
+            InitialVelocity::Zero => {}
+            InitialVelocity::Fixed { velocity: (x, y) } => {
+                if !(x.is_finite() && y.is_finite()) {
+                    return Err(ScenarioError::Invalid("initial velocity must be finite"));
+                }
+            }
+            InitialVelocity::Random {
+                min_speed,
+                max_speed,
+            } => {
+                if !(min_speed >= 0. && max_speed.is_finite()) {
+                    return Err(ScenarioError::Invalid(
+                        "initial speeds must be finite and non negative",
+                    ));
+                }
+                if min_speed > max_speed {
+                    return Err(ScenarioError::Invalid(
+                        "min_speed must not be above max_speed",
+                    ));
+                }
+            }
+        }
+        self.params.validate().map_err(ScenarioError::Params)?;
+        self.predator_params
+            .validate()
+            .map_err(ScenarioError::Predators)?;
+        for obstacle in &self.obstacles {
+            obstacle.validate().map_err(ScenarioError::Obstacle)?;
+        }
+        for attractor in &self.attractors {
+            attractor.validate().map_err(ScenarioError::Goal)?;
+        }
+        if let Some(route) = &self.route {
+            route.validate().map_err(ScenarioError::Goal)?;
+        }
+        self.species_and_interactions().map(|_| ())
+    }
+
+    /// Number of boids spawned, over all the species.
+    pub fn total_boid_count(&self) -> usize {
+        if self.species.is_empty() {
+            self.boid_count
+        } else {
+            self.species.iter().map(|s| s.count).sum()
+        }
+    }
+
+    pub fn species_and_interactions(
+        &self,
+    ) -> Result<(Vec<Species>, InteractionMatrix), ScenarioError> {
+        if self.species.is_empty() {
+            let species = Species::new("default", self.params.clone());
+            return Ok((
+                vec![species],
+                InteractionMatrix::uniform(1, Interaction::Flock),
+            ));
+        }
+
+        let species: Vec<_> = self.species.iter().map(|s| s.species.clone()).collect();
+        let interactions = if self.interactions.is_empty() {
+            InteractionMatrix::uniform(species.len(), Interaction::Flock)
+        } else {
+            InteractionMatrix::from_rows(&self.interactions).map_err(ScenarioError::Species)?
+        };
+        Species::validate_all(&species, &interactions).map_err(ScenarioError::Species)?;
+        Ok((species, interactions))
+    }
+
+    /// Spawn the boids, drawing every random value from `rng`.
+    pub fn spawn_boids(&self, rng: &mut Rng) -> Vec<Boid> {
+        let (w, h) = self.world_size;
+        let center = Vec2::new(w / 2., h / 2.);
+        let boid_count = self.total_boid_count();
+
+        let mut boids = match self.spawn {
+            SpawnDistribution::Spiral => spawn_spiral(boid_count, self.world_size),
+            SpawnDistribution::Uniform => (0..boid_count)
+                .map(|i| Boid::new((rng.range_f32(0., w), rng.range_f32(0., h)), i))
+                .collect(),
+            SpawnDistribution::Disc {
+                center: disc_center,
+                radius,
+            } => {
+                let disc_center = disc_center.map_or(center, Vec2::from);
+                (0..boid_count)
+                    .map(|i| {
+                        let angle = rng.range_f32(0., std::f32::consts::TAU);
+                        let r = radius * rng.next_f32().sqrt();
+                        let pos = disc_center + Vec2::new(angle.cos(), angle.sin()) * r;
+                        Boid::new((pos[0], pos[1]), i)
+                    })
+                    .collect()
+            }
+            SpawnDistribution::Grid { spacing } => {
+                let side = (boid_count as f32).sqrt().ceil() as usize;
+                let origin =
+                    center - Vec2::new(1., 1.) * (spacing * side.saturating_sub(1) as f32 / 2.);
+                (0..boid_count)
+                    .map(|i| {
+                        let pos =
+                            origin + Vec2::new((i % side) as f32, (i / side) as f32) * spacing;
+                        Boid::new((pos[0], pos[1]), i)
+                    })
+                    .collect()
+            }
+        };
+
+        for boid in &mut boids {
+            boid.velocity = match self.initial_velocity {
+                InitialVelocity::Zero => Vec2::zero(),
+                InitialVelocity::Fixed { velocity } => velocity.into(),
+                InitialVelocity::Random {
+                    min_speed,
+                    max_speed,
+                } => {
+                    let angle = rng.range_f32(0., std::f32::consts::TAU);
+                    Vec2::new(angle.cos(), angle.sin()) * rng.range_f32(min_speed, max_speed)
+                }
+            };
+        }
+
+        // species by consecutive ranges of boids
+        let mut boid_species = self
+            .species
+            .iter()
+            .enumerate()
+            .flat_map(|(index, s)| std::iter::repeat_n((index, s), s.count));
+        for boid in &mut boids {
+            if let Some((index, s)) = boid_species.next() {
+                boid.set_species(index);
+                if let Some(color) = s.species.color {
+                    boid.set_color(color.into());
+                }
+            }
+        }
+
+        boids
+    }
+
+    pub fn build(&self) -> Result<Simulation, ScenarioError> {
+        self.validate()?;
+        let mut rng = Rng::new(self.seed);
+        let boids = self.spawn_boids(&mut rng);
+        let (species, interactions) = self.species_and_interactions()?;
+        let mut sim = Simulation::new(boids, self.world_size);
+        sim.set_species(species, interactions)
+            .map_err(ScenarioError::Species)?;
+        sim.set_timestep(FixedTimestep::new(
+            self.physics_hz,
+            self.max_steps_per_frame,
+        ));
+        sim.set_integrator(self.integrator);
+        sim.set_spatial_index(self.spatial_index);
+        sim.set_boundary(self.boundary);
+        sim.set_predator_params(self.predator_params.clone());
+        for obstacle in &self.obstacles {
+            sim.add_obstacle(obstacle.clone())
+                .map_err(ScenarioError::Obstacle)?;
+        }
+        for attractor in &self.attractors {
+            sim.add_attractor(*attractor);
+        }
+        sim.set_route(self.route.clone());
+        let (w, h) = self.world_size;
+        for _ in 0..self.predator_count {
+            sim.add_predator((rng.range_f32(0., w), rng.range_f32(0., h)));
+        }
+        sim.set_rng(rng);
+        Ok(sim)
+    }
+
+    /// Spawn the boids of the 3D mode, the 2D distributions being extended along z :
+    /// uniform in the box, in a ball, on a cubic grid or along the spiral.
+    pub fn spawn_boids_3d(&self, rng: &mut Rng) -> Vec<Boid3> {
+        let (w, h) = self.world_size;
+        let d = self.depth;
+        let center = Vec3::new(w / 2., h / 2., d / 2.);
+        let boid_count = self.total_boid_count();
+
+        let mut boids: Vec<_> = match self.spawn {
+            SpawnDistribution::Spiral => spawn_spiral(boid_count, self.world_size)
+                .into_iter()
+                .enumerate()
+                .map(|(i, boid)| {
+                    let z = d / 2. + i as f32 * 4. + f32::sin(i as f32 * 0.5) * (d / 150.);
+                    Boid3::new(Vec3::new(boid.position[0], boid.position[1], z), i)
+                })
+                .collect(),
+            SpawnDistribution::Uniform => (0..boid_count)
+                .map(|i| {
+                    let pos = Vec3::new(
+                        rng.range_f32(0., w),
+                        rng.range_f32(0., h),
+                        rng.range_f32(0., d),
+                    );
+                    Boid3::new(pos, i)
+                })
+                .collect(),
+            SpawnDistribution::Disc {
+                center: disc_center,
+                radius,
+            } => {
+                let ball_center = disc_center.map_or(center, |(x, y)| Vec3::new(x, y, d / 2.));
+                (0..boid_count)
+                    .map(|i| {
+                        let r = radius * rng.next_f32().cbrt();
+                        let pos = ball_center + random_direction(rng) * r;
+                        Boid3::new(pos, i)
+                    })
+                    .collect()
+            }
+            SpawnDistribution::Grid { spacing } => {
+                let side = (boid_count as f32).cbrt().ceil() as usize;
+                let origin =
+                    center - Vec3::new(1., 1., 1.) * (spacing * side.saturating_sub(1) as f32 / 2.);
+                (0..boid_count)
+                    .map(|i| {
+                        let cell = Vec3::new(
+                            (i % side) as f32,
+                            (i / side % side) as f32,
+                            (i / (side * side)) as f32,
+                        );
+                        Boid3::new(origin + cell * spacing, i)
+                    })
+                    .collect()
+            }
+        };
+
+        for boid in &mut boids {
+            boid.velocity = match self.initial_velocity {
+                InitialVelocity::Zero => Vec3::zero(),
+                InitialVelocity::Fixed { velocity: (x, y) } => Vec3::new(x, y, 0.),
+                InitialVelocity::Random {
+                    min_speed,
+                    max_speed,
+                } => random_direction(rng) * rng.range_f32(min_speed, max_speed),
+            };
+        }
+
+        boids
+    }
+
+    /// Build the flock of the 3D mode. It has a single species with `params`,
+    /// the predators, obstacles, goals and species of the scenario being ignored.
+    pub fn build_3d(&self) -> Result<Simulation3, ScenarioError> {
+        self.validate()?;
+        let mut rng = Rng::new(self.seed);
+        let boids = self.spawn_boids_3d(&mut rng);
+        let (w, h) = self.world_size;
+        let mut sim =
+            Simulation3::with_params(boids, Vec3::new(w, h, self.depth), self.params.clone())
+                .map_err(ScenarioError::Params)?;
+        sim.set_timestep(FixedTimestep::new(
+            self.physics_hz,
+            self.max_steps_per_frame,
+        ));
+        sim.set_integrator(self.integrator);
+        sim.set_boundary(self.boundary);
+        sim.set_rng(rng);
+        Ok(sim)
+    }
+}
+
+/// Unit vector uniform over the sphere.
+fn random_direction(rng: &mut Rng) -> Vec3 {
+    let z = rng.range_f32(-1., 1.);
+    let angle = rng.range_f32(0., std::f32::consts::TAU);
+    let r = (1. - z * z).sqrt();
+    Vec3::new(r * angle.cos(), r * angle.sin(), z)
+}
+
+#[derive(Debug)]
+pub enum ScenarioError {
+    Io(String, std::io::Error),
+    Toml(toml::de::Error),
+    Ron(ron::error::SpannedError),
+    UnknownFormat(String),
+    Invalid(&'static str),
+    Params(ParamsError),
+    Predators(ParamsError),
+    Species(SpeciesError),
+    Obstacle(ObstacleError),
+    Goal(GoalError),
+}
+
+impl fmt::Display for ScenarioError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScenarioError::Io(path, err) => write!(f, "could not read scenario {path} : {err}"),
+            ScenarioError::Toml(err) => write!(f, "invalid TOML scenario : {err}"),
+            ScenarioError::Ron(err) => write!(f, "invalid RON scenario : {err}"),
+            ScenarioError::UnknownFormat(path) => {
+                write!(
+                    f,
+                    "unknown scenario format for {path}, expected .toml or .ron"
+                )
+            }
+            ScenarioError::Invalid(reason) => write!(f, "invalid scenario : {reason}"),
+            ScenarioError::Params(err) => write!(f, "invalid scenario : {err}"),
+            ScenarioError::Predators(err) => write!(f, "invalid scenario : predator {err}"),
+            ScenarioError::Species(err) => write!(f, "invalid scenario : {err}"),
+            ScenarioError::Obstacle(err) => write!(f, "invalid scenario : {err}"),
+            ScenarioError::Goal(err) => write!(f, "invalid scenario : {err}"),
+        }
+    }
+}
+
+impl std::error::Error for ScenarioError {}
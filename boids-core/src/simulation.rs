@@ -0,0 +1,520 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    boid::{Boid, Steering, handle_border, resolve_obstacle_collisions},
+    boids::Boids,
+    goal::{Attractor, Route},
+    integrator::Integrator,
+    math::Vec2,
+    obstacle::{Obstacle, ObstacleError},
+    parallel,
+    params::{FlockParams, ParamsError},
+    predator::{Predator, PredatorParams},
+    quadtree::Aabb,
+    rng::Rng,
+    spatial::{SpatialIndex, SpatialIndexKind, gather_neighbours},
+    species::{Interaction, InteractionMatrix, Species, SpeciesError},
+    timestep::FixedTimestep,
+    world::{Boundary, World},
+};
+
+/// Complete state of a 2D flock, serializable to resume it exactly, see [`crate::snapshot`].
+#[derive(Serialize, Deserialize)]
+pub struct Simulation {
+    boids: Boids,
+    /// Where the neighbour pass writes the steering of the boids, swapped with theirs after.
+    #[serde(skip)]
+    next_steering: Vec<Steering>,
+    #[serde(skip, default = "parallel::enabled_by_default")]
+    parallel: bool,
+    #[serde(default)]
+    spatial_index: SpatialIndexKind,
+    /// Built on the next step when missing, its cells depending on the perception radius.
+    #[serde(skip)]
+    index: Option<Box<dyn SpatialIndex + Send + Sync>>,
+    /// Never empty, the steering parameters of a boid being the ones of its species.
+    species: Vec<Species>,
+    interactions: InteractionMatrix,
+    predators: Vec<Predator>,
+    predator_params: PredatorParams,
+    obstacles: Vec<Obstacle>,
+    attractors: Vec<Attractor>,
+    /// Attractor following the user, kept apart from the others as it moves every frame.
+    pointer: Option<Attractor>,
+    route: Option<Route>,
+
+    world: World,
+    timestep: FixedTimestep,
+    integrator: Integrator,
+
+    rng: Rng,
+    step_count: u64,
+    time: f64,
+}
+
+impl Simulation {
+    pub fn new(boids: Vec<Boid>, world_size: (f32, f32)) -> Self {
+        Self {
+            boids: boids.into(),
+            next_steering: Vec::new(),
+            parallel: parallel::enabled_by_default(),
+            spatial_index: SpatialIndexKind::default(),
+            index: None,
+            species: vec![Species::default()],
+            interactions: InteractionMatrix::uniform(1, Interaction::Flock),
+            predators: Vec::new(),
+            predator_params: PredatorParams::default(),
+            obstacles: Vec::new(),
+            attractors: Vec::new(),
+            pointer: None,
+            route: None,
+            world: World::new(world_size, Boundary::default()),
+            timestep: FixedTimestep::default(),
+            integrator: Integrator::default(),
+
+            rng: Rng::new(0),
+            step_count: 0,
+            time: 0.,
+        }
+    }
+
+    pub fn with_params(
+        boids: Vec<Boid>,
+        world_size: (f32, f32),
+        params: FlockParams,
+    ) -> Result<Self, ParamsError> {
+        let mut sim = Self::new(boids, world_size);
+        sim.set_params(params)?;
+        Ok(sim)
+    }
+
+    /// Steering parameters of the first species, the only one unless others were set.
+    pub fn params(&self) -> &FlockParams {
+        &self.species[0].params
+    }
+
+    /// Replace the steering parameters of the first species,
+    /// its boids use them from the next step on.
+    /// The neighbour mode being shared, it is set for every species.
+    pub fn set_params(&mut self, params: FlockParams) -> Result<(), ParamsError> {
+        params.validate()?;
+        for s in &mut self.species[1..] {
+            s.params.neighbours = params.neighbours;
+        }
+        self.species[0].params = params;
+        self.index = None;
+        Ok(())
+    }
+
+    pub fn species(&self) -> &[Species] {
+        &self.species
+    }
+
+    pub fn interactions(&self) -> &InteractionMatrix {
+        &self.interactions
+    }
+
+    /// Replace the species and how they react to each other.
+    /// Every species must find its neighbours the same way,
+    /// and boids of a species that no longer exists fall back to the first one.
+    pub fn set_species(
+        &mut self,
+        species: Vec<Species>,
+        interactions: InteractionMatrix,
+    ) -> Result<(), SpeciesError> {
+        Species::validate_all(&species, &interactions)?;
+
+        for s in &mut self.boids.species {
+            if *s >= species.len() {
+                *s = 0;
+            }
+        }
+        self.species = species;
+        self.interactions = interactions;
+        self.index = None;
+        Ok(())
+    }
+
+    pub fn predators(&self) -> &[Predator] {
+        &self.predators
+    }
+
+    pub fn predator_params(&self) -> &PredatorParams {
+        &self.predator_params
+    }
+
+    pub fn set_predator_params(&mut self, params: PredatorParams) {
+        self.predator_params = params;
+    }
+
+    pub fn add_predator(&mut self, pos: (f32, f32)) {
+        self.predators.push(Predator::new(pos));
+    }
+
+    pub fn obstacles(&self) -> &[Obstacle] {
+        &self.obstacles
+    }
+
+    pub fn add_obstacle(&mut self, obstacle: Obstacle) -> Result<(), ObstacleError> {
+        obstacle.validate()?;
+        self.obstacles.push(obstacle);
+        Ok(())
+    }
+
+    pub fn clear_obstacles(&mut self) {
+        self.obstacles.clear();
+    }
+
+    pub fn attractors(&self) -> &[Attractor] {
+        &self.attractors
+    }
+
+    pub fn add_attractor(&mut self, attractor: Attractor) {
+        self.attractors.push(attractor);
+    }
+
+    pub fn clear_attractors(&mut self) {
+        self.attractors.clear();
+    }
+
+    pub fn pointer(&self) -> Option<&Attractor> {
+        self.pointer.as_ref()
+    }
+
+    pub fn set_pointer(&mut self, pointer: Option<Attractor>) {
+        self.pointer = pointer;
+    }
+
+    pub fn route(&self) -> Option<&Route> {
+        self.route.as_ref()
+    }
+
+    /// Replace the route, every boid starting again from its first waypoint.
+    pub fn set_route(&mut self, route: Option<Route>) {
+        self.route = route;
+        self.boids.waypoints.fill(0);
+    }
+
+    /// Generator every stochastic behaviour must draw from, for runs to be reproducible.
+    pub fn rng_mut(&mut self) -> &mut Rng {
+        &mut self.rng
+    }
+
+    pub fn set_rng(&mut self, rng: Rng) {
+        self.rng = rng;
+    }
+
+    /// Number of physics steps since creation.
+    pub fn step_count(&self) -> u64 {
+        self.step_count
+    }
+
+    /// Simulated time since creation, in seconds.
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    pub fn len(&self) -> usize {
+        self.boids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.boids.is_empty()
+    }
+
+    pub fn boids(&self) -> &Boids {
+        &self.boids
+    }
+
+    pub fn world_size(&self) -> (f32, f32) {
+        (self.world.size[0], self.world.size[1])
+    }
+
+    pub fn world(&self) -> &World {
+        &self.world
+    }
+
+    pub fn boundary(&self) -> Boundary {
+        self.world.boundary
+    }
+
+    pub fn set_boundary(&mut self, boundary: Boundary) {
+        self.world.boundary = boundary;
+    }
+
+    pub fn timestep(&self) -> &FixedTimestep {
+        &self.timestep
+    }
+
+    pub fn set_timestep(&mut self, timestep: FixedTimestep) {
+        self.timestep = timestep;
+    }
+
+    pub fn integrator(&self) -> Integrator {
+        self.integrator
+    }
+
+    pub fn set_integrator(&mut self, integrator: Integrator) {
+        self.integrator = integrator;
+    }
+
+    pub fn spatial_index(&self) -> SpatialIndexKind {
+        self.spatial_index
+    }
+
+    /// Find the neighbours with another kind of index, the trajectories staying the same.
+    pub fn set_spatial_index(&mut self, spatial_index: SpatialIndexKind) {
+        self.spatial_index = spatial_index;
+        self.index = None;
+    }
+
+    /// Whether the steps run on all the cores, only possible with the `parallel` feature.
+    pub fn is_parallel(&self) -> bool {
+        self.parallel
+    }
+
+    /// Run the steps on all the cores or on the calling thread,
+    /// both giving bit-identical results. Parallel by default.
+    #[cfg(feature = "parallel")]
+    pub fn set_parallel(&mut self, parallel: bool) {
+        self.parallel = parallel;
+    }
+
+    /// Interpolation factor between the previous and the current physics step,
+    /// see [`Boid::interpolated_position`].
+    pub fn alpha(&self) -> f32 {
+        self.timestep.alpha()
+    }
+
+    pub fn bound(&self) -> Aabb {
+        self.world.bound()
+    }
+
+    /// Mean position of the flock, the world center when empty.
+    pub fn center_of_mass(&self) -> Vec2 {
+        if self.boids.is_empty() {
+            return self.world.size * 0.5;
+        }
+        let sum = self
+            .boids
+            .positions()
+            .iter()
+            .fold(Vec2::zero(), |acc, &position| acc + position);
+        sum / self.boids.len() as f32
+    }
+
+    pub fn average_speed(&self) -> f32 {
+        let sum: f32 = self.boids.velocities().iter().map(|v| v.length()).sum();
+        sum / self.boids.len().max(1) as f32
+    }
+
+    pub fn add_boid(&mut self, pos: (f32, f32)) {
+        let boid = Boid::new(pos, self.boids.len());
+        self.boids.push(boid);
+    }
+
+    pub fn resize(&mut self, world_size: (f32, f32)) {
+        self.world.size = world_size.into();
+        self.boids.for_each_mut(false, |boid| {
+            handle_border(
+                boid.position,
+                boid.velocity,
+                boid.previous_position,
+                boid.size,
+                &self.species[boid.species].params,
+                &self.world,
+            );
+        });
+        for predator in &mut self.predators {
+            predator.handle_border_colision(&self.predator_params, &self.world);
+        }
+    }
+
+    /// Feed a frame time to the fixed-step accumulator and run the physics steps that are due.
+    /// Returns the number of steps run.
+    pub fn advance(&mut self, frame_dt: f32) -> u32 {
+        self.advance_with(frame_dt, |_| ())
+    }
+
+    /// Same as [`Simulation::advance`], calling `on_step` after each step.
+    pub fn advance_with(&mut self, frame_dt: f32, mut on_step: impl FnMut(&Self)) -> u32 {
+        let steps = self.timestep.accumulate(frame_dt);
+        for _ in 0..steps {
+            self.step();
+            on_step(self);
+        }
+        steps
+    }
+
+    /// Run exactly one physics step of [`FixedTimestep::dt`].
+    pub fn step(&mut self) {
+        let dt = self.timestep.dt();
+        self.physic_step(dt);
+        self.step_count += 1;
+        self.time += f64::from(dt);
+    }
+
+    /// FNV-1a hash of the bits of every boid position and velocity,
+    /// two runs with the same scenario and the same dt have the same hash.
+    pub fn state_hash(&self) -> u64 {
+        let positions = self.boids.positions().iter();
+        fnv_hash(
+            positions
+                .zip(self.boids.velocities())
+                .flat_map(|(position, velocity)| {
+                    position.as_array().iter().chain(velocity.as_array())
+                })
+                .copied(),
+        )
+    }
+
+    /// Every neighbour is read from the state at the start of the step and every steering
+    /// written to `next_steering`, so the result does not depend on the order the boids are
+    /// visited in, nor on how they are split between threads.
+    fn physic_step(&mut self, dt: f32) {
+        let world = &self.world;
+        let species = &self.species;
+        let interactions = &self.interactions;
+        let obstacles = &self.obstacles;
+        let neighbour_mode = species[0].params.neighbours;
+        let radius = species
+            .iter()
+            .map(|s| s.params.perception_radius())
+            .fold(0., f32::max);
+        let integrator = self.integrator;
+        self.boids.for_each_mut(self.parallel, |boid| {
+            let params = &species[boid.species].params;
+            *boid.previous_position = *boid.position;
+            boid.steering.reset();
+            handle_border(
+                boid.position,
+                boid.velocity,
+                boid.previous_position,
+                boid.size,
+                params,
+                world,
+            );
+            boid.steering
+                .perceive_obstacles(params, world, *boid.position, boid.size, obstacles);
+            for attractor in self.attractors.iter().chain(&self.pointer) {
+                boid.steering
+                    .perceive_attractor(params, world, boid.state(), attractor);
+            }
+            if let Some(route) = &self.route {
+                let me = boid.state();
+                boid.steering
+                    .follow_route(params, world, me, boid.waypoint, route);
+            }
+        });
+
+        let boids = &self.boids;
+        let positions = boids.positions();
+        let predators = &self.predators;
+        let bound = world.bound();
+        let spatial_index = self.spatial_index;
+        let index = self
+            .index
+            .get_or_insert_with(|| spatial_index.build(bound, radius));
+        index.rebuild(bound, positions);
+        let index = &**index;
+        let order = || {
+            let positions = positions.iter().copied();
+            parallel::spatial_order(positions, Vec2::zero(), world.size)
+        };
+        parallel::update_into(
+            &boids.steering,
+            &mut self.next_steering,
+            self.parallel,
+            order,
+            |i, steering, neighbours| {
+                let me = boids.state(i);
+                gather_neighbours(
+                    index,
+                    neighbour_mode,
+                    i,
+                    radius,
+                    world,
+                    |j| positions[j],
+                    neighbours,
+                );
+
+                let a = boids.species[i];
+                let params = &species[a].params;
+                for &j in neighbours.iter() {
+                    let other = boids.state(j);
+                    let interaction = interactions.get(a, boids.species[j]);
+                    steering.perceive(params, world, interaction, me, other);
+                    steering.perceive_collision(world, me, other);
+                }
+                // predators scare the boids around them
+                for predator in predators {
+                    steering.perceive_predator(params, world, me.position, predator.position);
+                }
+            },
+        );
+        std::mem::swap(&mut self.boids.steering, &mut self.next_steering);
+
+        // predators chase the nearest boid
+        let positions = self.boids.positions();
+        let mut neighbours = Vec::new();
+        for predator in &mut self.predators {
+            neighbours.clear();
+            match world.period() {
+                Some(period) => {
+                    index.k_nearest_wrapped(predator.position, 1, period, &mut neighbours)
+                }
+                None => index.k_nearest(predator.position, 1, &mut neighbours),
+            }
+            let prey = neighbours.first().map(|&i| positions[i]);
+            predator.chase(&self.predator_params, world, prey, dt);
+            predator.handle_border_colision(&self.predator_params, world);
+            resolve_obstacle_collisions(
+                &mut predator.position,
+                &mut predator.velocity,
+                self.predator_params.size,
+                world,
+                obstacles,
+            );
+        }
+
+        self.boids.for_each_mut(self.parallel, |boid| {
+            let params = &species[boid.species].params;
+            // overlapping boids are pushed apart before moving
+            *boid.position += boid.steering.push();
+            *boid.avg_color = boid.steering.blended_color(boid.color);
+            boid.steering
+                .apply_forces(params, integrator, dt, boid.position, boid.velocity);
+            resolve_obstacle_collisions(boid.position, boid.velocity, boid.size, world, obstacles);
+        });
+    }
+}
+
+/// The spawn pattern of the windowed app : a small spiral around the world center.
+pub fn spawn_spiral(count: usize, (r1, r2): (f32, f32)) -> Vec<Boid> {
+    (0..count)
+        .map(|i| {
+            Boid::new(
+                (
+                    r1 / 2. + i as f32 * 4. + f32::cos(i as f32) * (r2 / 150.),
+                    r2 / 2. + i as f32 * 4. + f32::sin(i as f32) * (r1 / 150.),
+                ),
+                i,
+            )
+        })
+        .collect()
+}
+
+/// FNV-1a hash of the bits of `values`, in order.
+pub(crate) fn fnv_hash(values: impl Iterator<Item = f32>) -> u64 {
+    const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let mut hash = FNV_OFFSET;
+    for value in values {
+        for byte in value.to_bits().to_le_bytes() {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(FNV_PRIME);
+        }
+    }
+    hash
+}
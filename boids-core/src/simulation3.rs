@@ -0,0 +1,251 @@
+use crate::{
+    boid3::Boid3,
+    integrator::Integrator,
+    math::Vec3,
+    octree::Octree,
+    parallel,
+    params::{FlockParams, ParamsError},
+    rng::Rng,
+    simulation::fnv_hash,
+    spatial::gather_neighbours,
+    timestep::FixedTimestep,
+    world::{Boundary, World},
+};
+
+/// Flock of the 3D mode : a single species of [`Boid3`] in a box,
+/// without predators, obstacles, goals or color blending.
+pub struct Simulation3 {
+    boids: Vec<Boid3>,
+    /// Where the next state of the boids is written during a step, swapped with `boids` after.
+    next: Vec<Boid3>,
+    parallel: bool,
+    params: FlockParams,
+
+    world: World<Vec3>,
+    timestep: FixedTimestep,
+    integrator: Integrator,
+
+    rng: Rng,
+    step_count: u64,
+    time: f64,
+}
+
+impl Simulation3 {
+    pub fn new(boids: Vec<Boid3>, world_size: Vec3) -> Self {
+        Self {
+            boids,
+            next: Vec::new(),
+            parallel: parallel::enabled_by_default(),
+            params: FlockParams::default(),
+            world: World::with_size(world_size, Boundary::default()),
+            timestep: FixedTimestep::default(),
+            integrator: Integrator::default(),
+
+            rng: Rng::new(0),
+            step_count: 0,
+            time: 0.,
+        }
+    }
+
+    pub fn with_params(
+        boids: Vec<Boid3>,
+        world_size: Vec3,
+        params: FlockParams,
+    ) -> Result<Self, ParamsError> {
+        let mut sim = Self::new(boids, world_size);
+        sim.set_params(params)?;
+        Ok(sim)
+    }
+
+    pub fn params(&self) -> &FlockParams {
+        &self.params
+    }
+
+    /// Replace the steering parameters, the boids use them from the next step on.
+    pub fn set_params(&mut self, params: FlockParams) -> Result<(), ParamsError> {
+        params.validate()?;
+        self.params = params;
+        Ok(())
+    }
+
+    /// Generator every stochastic behaviour must draw from, for runs to be reproducible.
+    pub fn rng_mut(&mut self) -> &mut Rng {
+        &mut self.rng
+    }
+
+    pub fn set_rng(&mut self, rng: Rng) {
+        self.rng = rng;
+    }
+
+    /// Number of physics steps since creation.
+    pub fn step_count(&self) -> u64 {
+        self.step_count
+    }
+
+    /// Simulated time since creation, in seconds.
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    pub fn len(&self) -> usize {
+        self.boids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.boids.is_empty()
+    }
+
+    pub fn boids(&self) -> &[Boid3] {
+        &self.boids
+    }
+
+    pub fn world(&self) -> &World<Vec3> {
+        &self.world
+    }
+
+    pub fn boundary(&self) -> Boundary {
+        self.world.boundary
+    }
+
+    pub fn set_boundary(&mut self, boundary: Boundary) {
+        self.world.boundary = boundary;
+    }
+
+    pub fn timestep(&self) -> &FixedTimestep {
+        &self.timestep
+    }
+
+    pub fn set_timestep(&mut self, timestep: FixedTimestep) {
+        self.timestep = timestep;
+    }
+
+    pub fn integrator(&self) -> Integrator {
+        self.integrator
+    }
+
+    pub fn set_integrator(&mut self, integrator: Integrator) {
+        self.integrator = integrator;
+    }
+
+    pub fn is_parallel(&self) -> bool {
+        self.parallel
+    }
+
+    /// See [`Simulation::set_parallel`](crate::Simulation::set_parallel).
+    #[cfg(feature = "parallel")]
+    pub fn set_parallel(&mut self, parallel: bool) {
+        self.parallel = parallel;
+    }
+
+    /// Interpolation factor between the previous and the current physics step,
+    /// see [`Boid3::interpolated_position`].
+    pub fn alpha(&self) -> f32 {
+        self.timestep.alpha()
+    }
+
+    /// Mean position of the flock, the world center when empty.
+    pub fn center_of_mass(&self) -> Vec3 {
+        if self.boids.is_empty() {
+            return self.world.size * 0.5;
+        }
+        let sum = self
+            .boids
+            .iter()
+            .fold(Vec3::zero(), |acc, boid| acc + boid.position);
+        sum / self.boids.len() as f32
+    }
+
+    pub fn average_speed(&self) -> f32 {
+        let sum: f32 = self.boids.iter().map(|boid| boid.velocity.length()).sum();
+        sum / self.boids.len().max(1) as f32
+    }
+
+    pub fn add_boid(&mut self, pos: Vec3) {
+        let boid = Boid3::new(pos, self.boids.len());
+        self.boids.push(boid);
+    }
+
+    /// Feed a frame time to the fixed-step accumulator and run the physics steps that are due.
+    /// Returns the number of steps run.
+    pub fn advance(&mut self, frame_dt: f32) -> u32 {
+        let steps = self.timestep.accumulate(frame_dt);
+        for _ in 0..steps {
+            self.step();
+        }
+        steps
+    }
+
+    /// Run exactly one physics step of [`FixedTimestep::dt`].
+    pub fn step(&mut self) {
+        let dt = self.timestep.dt();
+        self.physic_step(dt);
+        self.step_count += 1;
+        self.time += f64::from(dt);
+    }
+
+    /// FNV-1a hash of the bits of every boid position and velocity,
+    /// see [`crate::Simulation::state_hash`].
+    pub fn state_hash(&self) -> u64 {
+        fnv_hash(
+            self.boids
+                .iter()
+                .flat_map(|boid| {
+                    boid.position
+                        .as_array()
+                        .iter()
+                        .chain(boid.velocity.as_array())
+                })
+                .copied(),
+        )
+    }
+
+    /// Every neighbour is read from the state at the start of the step and every update
+    /// written to `next`, as in the 2D simulation.
+    fn physic_step(&mut self, dt: f32) {
+        let world = &self.world;
+        let params = &self.params;
+        let radius = params.perception_radius();
+        parallel::for_each(&mut self.boids, self.parallel, |boid| {
+            boid.previous_position = boid.position;
+            boid.reset_forces();
+            boid.handle_border_colision(params, world);
+        });
+
+        let boids = &self.boids;
+        let tree = Octree::build(world.bound(), boids.iter().map(|b| b.position));
+        let order = || {
+            let positions = boids.iter().map(|b| b.position);
+            parallel::spatial_order(positions, Vec3::zero(), world.size)
+        };
+        parallel::update_into(
+            boids,
+            &mut self.next,
+            self.parallel,
+            order,
+            |i, boid, neighbours| {
+                gather_neighbours(
+                    &tree,
+                    params.neighbours,
+                    i,
+                    radius,
+                    world,
+                    |j| boids[j].position,
+                    neighbours,
+                );
+
+                let mut push = Vec3::zero();
+                for &j in neighbours.iter() {
+                    boid.perceive(params, world, &boids[j]);
+                    push += boid.collision_push(world, &boids[j]);
+                }
+                boid.position += push;
+            },
+        );
+
+        let integrator = self.integrator;
+        parallel::for_each(&mut self.next, self.parallel, |boid| {
+            boid.apply_forces(params, integrator, dt);
+        });
+        std::mem::swap(&mut self.boids, &mut self.next);
+    }
+}
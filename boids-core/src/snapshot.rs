@@ -0,0 +1,131 @@
+//! Versioned RON files holding the complete state of a [`Simulation`],
+//! loading one resumes the simulation exactly where it was saved.
+
+use std::{fmt, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    goal::GoalError, obstacle::ObstacleError, params::ParamsError, simulation::Simulation,
+    species::SpeciesError,
+};
+
+/// Version written in every snapshot, bumped whenever the state of [`Simulation`] changes.
+pub const VERSION: u32 = 2;
+
+#[derive(Serialize)]
+struct SnapshotRef<'a> {
+    version: u32,
+    simulation: &'a Simulation,
+}
+
+/// The version being checked beforehand by reading the [`Header`].
+#[derive(Deserialize)]
+struct Snapshot {
+    simulation: Simulation,
+}
+
+/// Read before the rest, for a file of another version not to be reported as a parse error.
+#[derive(Deserialize)]
+struct Header {
+    version: u32,
+}
+
+pub fn save(sim: &Simulation, path: impl AsRef<Path>) -> Result<(), SnapshotError> {
+    let path = path.as_ref();
+    let s = to_string(sim)?;
+    std::fs::write(path, s).map_err(|err| SnapshotError::Io(path.display().to_string(), err))
+}
+
+pub fn load(path: impl AsRef<Path>) -> Result<Simulation, SnapshotError> {
+    let path = path.as_ref();
+    let s = std::fs::read_to_string(path)
+        .map_err(|err| SnapshotError::Io(path.display().to_string(), err))?;
+    from_str(&s)
+}
+
+pub fn to_string(sim: &Simulation) -> Result<String, SnapshotError> {
+    let snapshot = SnapshotRef {
+        version: VERSION,
+        simulation: sim,
+    };
+    // one line per boid
+    let config = ron::ser::PrettyConfig::default().depth_limit(3);
+    ron::ser::to_string_pretty(&snapshot, config).map_err(SnapshotError::Serialize)
+}
+
+pub fn from_str(s: &str) -> Result<Simulation, SnapshotError> {
+    let header: Header = ron::from_str(s).map_err(SnapshotError::Ron)?;
+    if header.version != VERSION {
+        return Err(SnapshotError::Version {
+            found: header.version,
+            expected: VERSION,
+        });
+    }
+
+    let Snapshot { simulation } = ron::from_str(s).map_err(SnapshotError::Ron)?;
+    let mut sim = simulation;
+    // checked as thoroughly as a scenario, the species parameters by `set_species` below
+    let (w, h) = sim.world_size();
+    if !(w > 0. && h > 0. && w.is_finite() && h.is_finite()) {
+        return Err(SnapshotError::Invalid(
+            "world size must be finite and positive",
+        ));
+    }
+    let hz = sim.timestep().hz();
+    if !(hz > 0. && hz.is_finite()) {
+        return Err(SnapshotError::Invalid(
+            "physics hz must be finite and positive",
+        ));
+    }
+    sim.predator_params()
+        .validate()
+        .map_err(SnapshotError::Predators)?;
+    for obstacle in sim.obstacles() {
+        obstacle.validate().map_err(SnapshotError::Obstacle)?;
+    }
+    for attractor in sim.attractors().iter().chain(sim.pointer()) {
+        attractor.validate().map_err(SnapshotError::Goal)?;
+    }
+    if let Some(route) = sim.route() {
+        route.validate().map_err(SnapshotError::Goal)?;
+    }
+    let (species, interactions) = (sim.species().to_vec(), sim.interactions().clone());
+    sim.set_species(species, interactions)
+        .map_err(SnapshotError::Species)?;
+    Ok(sim)
+}
+
+#[derive(Debug)]
+pub enum SnapshotError {
+    Io(String, std::io::Error),
+    Ron(ron::error::SpannedError),
+    Serialize(ron::Error),
+    Version { found: u32, expected: u32 },
+    Invalid(&'static str),
+    Predators(ParamsError),
+    Species(SpeciesError),
+    Obstacle(ObstacleError),
+    Goal(GoalError),
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapshotError::Io(path, err) => write!(f, "could not access snapshot {path} : {err}"),
+            SnapshotError::Ron(err) => write!(f, "invalid snapshot : {err}"),
+            SnapshotError::Serialize(err) => write!(f, "could not write snapshot : {err}"),
+            SnapshotError::Version { found, expected } => write!(
+                f,
+                "snapshot of version {found}, this build reads version {expected}"
+            ),
+            SnapshotError::Invalid(reason) => write!(f, "invalid snapshot : {reason}"),
+            SnapshotError::Predators(err) => write!(f, "invalid snapshot : predator {err}"),
+            SnapshotError::Species(err) => write!(f, "invalid snapshot : {err}"),
+            SnapshotError::Obstacle(err) => write!(f, "invalid snapshot : {err}"),
+            SnapshotError::Goal(err) => write!(f, "invalid snapshot : {err}"),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
@@ -0,0 +1,279 @@
+//! Spatial indices over the boid positions, used to find the neighbours of each boid.
+
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    grid::UniformGrid,
+    math::{Vec2, Vector},
+    params::NeighbourMode,
+    quadtree::Aabb,
+    quadtree::Quadtree,
+    steering::sort_by_position,
+    world::World,
+};
+
+/// Index of points identified by an index into an external slice.
+pub trait SpatialIndex {
+    /// Forget every point and index `positions`, index `i` referring to the `i`th position.
+    /// The bound grows to contain every position.
+    fn rebuild(&mut self, bound: Aabb, positions: &[Vec2]);
+
+    /// Add the point `index` at `pos`, false when `pos` is outside of the index bound.
+    fn insert(&mut self, pos: Vec2, index: usize) -> bool;
+
+    /// Remove the point `index` inserted at `pos`, false when there is no such point.
+    fn remove(&mut self, pos: Vec2, index: usize) -> bool;
+
+    /// Push into `out` the index of every point inside `range`.
+    fn query_range(&self, range: &Aabb, out: &mut Vec<usize>);
+
+    /// Push into `out` the index of the `k` points nearest to any of the `images`,
+    /// nearest first, ties being broken by index.
+    fn k_nearest_to_any(&self, images: &[Vec2], k: usize, out: &mut Vec<usize>);
+
+    /// Push into `out` the index of the `k` points nearest to `point`, nearest first,
+    /// ties being broken by index.
+    fn k_nearest(&self, point: Vec2, k: usize, out: &mut Vec<usize>) {
+        self.k_nearest_to_any(&[point], k, out);
+    }
+
+    /// Same as [`SpatialIndex::query_range`] in a world repeating itself every `period`
+    /// from the origin, `range` reaching across the edges.
+    /// The indices pushed are sorted and unique.
+    fn query_range_wrapped(&self, range: &Aabb, period: Vec2, out: &mut Vec<usize>) {
+        let start = out.len();
+        let world = Aabb::from_min_max(Vec2::zero(), period);
+        for dx in [-period[0], 0., period[0]] {
+            for dy in [-period[1], 0., period[1]] {
+                let shift = Vec2::new(dx, dy);
+                let image = Aabb::from_min_max(range.min + shift, range.max + shift);
+                if image.intersects(&world) {
+                    self.query_range(&image, out);
+                }
+            }
+        }
+        // a range wider than the world meets some points more than once
+        let mut found = out.split_off(start);
+        found.sort_unstable();
+        found.dedup();
+        out.append(&mut found);
+    }
+
+    /// Same as [`SpatialIndex::k_nearest`] in a world repeating itself every `period`
+    /// from the origin, `point` being inside that world.
+    fn k_nearest_wrapped(&self, point: Vec2, k: usize, period: Vec2, out: &mut Vec<usize>) {
+        // the nearest copy of any point is toward the closest edges
+        let mut shift = Vec2::zero();
+        for i in 0..2 {
+            shift[i] = if point[i] < period[i] * 0.5 {
+                period[i]
+            } else {
+                -period[i]
+            };
+        }
+        let images = [
+            point,
+            point + Vec2::new(shift[0], 0.),
+            point + Vec2::new(0., shift[1]),
+            point + shift,
+        ];
+        self.k_nearest_to_any(&images, k, out);
+    }
+}
+
+/// The queries neighbours are found with, over a [`SpatialIndex`] in 2D
+/// and an [`Octree`](crate::octree::Octree) in 3D.
+pub trait NeighbourQuery<V> {
+    /// Push into `out` the index of every point at most `radius` away from `center` on each axis,
+    /// in a world repeating itself every `period` when there is one.
+    fn query_around(&self, center: V, radius: f32, period: Option<V>, out: &mut Vec<usize>);
+
+    /// Push into `out` the index of the `k` points nearest to `point`, nearest first,
+    /// in a world repeating itself every `period` when there is one.
+    fn nearest(&self, point: V, k: usize, period: Option<V>, out: &mut Vec<usize>);
+}
+
+impl<T: SpatialIndex + ?Sized> NeighbourQuery<Vec2> for T {
+    fn query_around(&self, center: Vec2, radius: f32, period: Option<Vec2>, out: &mut Vec<usize>) {
+        let range = Aabb::from_center(center, radius);
+        match period {
+            Some(period) => self.query_range_wrapped(&range, period, out),
+            None => self.query_range(&range, out),
+        }
+    }
+
+    fn nearest(&self, point: Vec2, k: usize, period: Option<Vec2>, out: &mut Vec<usize>) {
+        match period {
+            Some(period) => self.k_nearest_wrapped(point, k, period, out),
+            None => self.k_nearest(point, k, out),
+        }
+    }
+}
+
+/// Replace the content of `out` by the neighbours of the point `i`, found as `mode` says
+/// within `radius`, sorted by position for the sums over them not to depend on the index.
+pub(crate) fn gather_neighbours<V: Vector>(
+    index: &(impl NeighbourQuery<V> + ?Sized),
+    mode: NeighbourMode,
+    i: usize,
+    radius: f32,
+    world: &World<V>,
+    position: impl Fn(usize) -> V,
+    out: &mut Vec<usize>,
+) {
+    out.clear();
+    match mode {
+        NeighbourMode::Metric => {
+            index.query_around(position(i), radius, world.period(), out);
+            out.retain(|&j| j != i);
+        }
+        NeighbourMode::Topological { k } => {
+            // one more than k, as the point itself is among the nearest
+            index.nearest(position(i), k + 1, world.period(), out);
+            out.retain(|&j| j != i);
+            out.truncate(k);
+        }
+    }
+    sort_by_position(out, position);
+}
+
+/// Squared distance from `pos` to the nearest of the `images`.
+pub(crate) fn distance_sq_to_any(images: &[Vec2], pos: Vec2) -> f32 {
+    images
+        .iter()
+        .map(|&image| pos.distance_sq(image))
+        .fold(f32::INFINITY, f32::min)
+}
+
+/// Keep in `best`, sorted by distance then index, the `k` nearest points seen so far.
+pub(crate) fn offer(best: &mut Vec<(f32, usize)>, k: usize, dist_sq: f32, index: usize) {
+    let rank = best.partition_point(|&(best_dist_sq, best_index)| {
+        best_dist_sq < dist_sq || (best_dist_sq == dist_sq && best_index < index)
+    });
+    // a point met twice, through two images
+    if rank < k && best.get(rank) != Some(&(dist_sq, index)) {
+        best.insert(rank, (dist_sq, index));
+        best.truncate(k);
+    }
+}
+
+/// Every point in a list, each query going through all of them.
+/// The reference the other indices are checked against, and the fastest on small flocks.
+#[derive(Debug, Clone, Default)]
+pub struct BruteForce {
+    points: Vec<(Vec2, usize)>,
+}
+
+impl BruteForce {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl SpatialIndex for BruteForce {
+    fn rebuild(&mut self, _bound: Aabb, positions: &[Vec2]) {
+        self.points.clear();
+        self.points
+            .extend(positions.iter().enumerate().map(|(i, &pos)| (pos, i)));
+    }
+
+    fn insert(&mut self, pos: Vec2, index: usize) -> bool {
+        self.points.push((pos, index));
+        true
+    }
+
+    fn remove(&mut self, pos: Vec2, index: usize) -> bool {
+        match self.points.iter().position(|&point| point == (pos, index)) {
+            Some(i) => {
+                self.points.swap_remove(i);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn query_range(&self, range: &Aabb, out: &mut Vec<usize>) {
+        out.extend(
+            self.points
+                .iter()
+                .filter(|(pos, _)| range.contains(*pos))
+                .map(|(_, index)| *index),
+        );
+    }
+
+    fn k_nearest_to_any(&self, images: &[Vec2], k: usize, out: &mut Vec<usize>) {
+        let mut best = Vec::with_capacity(k + 1);
+        if k > 0 {
+            for &(pos, index) in &self.points {
+                offer(&mut best, k, distance_sq_to_any(images, pos), index);
+            }
+        }
+        out.extend(best.into_iter().map(|(_, index)| index));
+    }
+}
+
+/// The spatial index a simulation finds neighbours with,
+/// every kind giving the same neighbours and so the same trajectories.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpatialIndexKind {
+    /// Point quadtree, adapting to any density.
+    #[default]
+    Quadtree,
+    /// Uniform grid of cells as wide as the largest perception radius,
+    /// the fastest to rebuild on flocks spread evenly.
+    Grid,
+    /// No index, every boid being checked against every other.
+    BruteForce,
+}
+
+impl SpatialIndexKind {
+    pub const ALL: [SpatialIndexKind; 3] = [
+        SpatialIndexKind::Quadtree,
+        SpatialIndexKind::Grid,
+        SpatialIndexKind::BruteForce,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            SpatialIndexKind::Quadtree => "quadtree",
+            SpatialIndexKind::Grid => "grid",
+            SpatialIndexKind::BruteForce => "brute_force",
+        }
+    }
+
+    /// An empty index over `bound`, grid cells being `cell_size` wide.
+    pub fn build(self, bound: Aabb, cell_size: f32) -> Box<dyn SpatialIndex + Send + Sync> {
+        match self {
+            SpatialIndexKind::Quadtree => Box::new(Quadtree::new(bound)),
+            SpatialIndexKind::Grid => Box::new(UniformGrid::new(bound, cell_size)),
+            SpatialIndexKind::BruteForce => Box::new(BruteForce::new()),
+        }
+    }
+}
+
+impl fmt::Display for SpatialIndexKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for SpatialIndexKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.replace('-', "_");
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.name() == s)
+            .ok_or_else(|| {
+                let names: Vec<_> = Self::ALL.iter().map(|kind| kind.name()).collect();
+                format!(
+                    "unknown spatial index `{s}`, expected one of {}",
+                    names.join(", ")
+                )
+            })
+    }
+}
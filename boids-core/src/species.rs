@@ -0,0 +1,201 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::params::{FlockParams, ParamsError};
+
+/// Named kind of boid, with its own steering parameters.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Species {
+    pub name: String,
+    #[serde(default)]
+    pub params: FlockParams,
+    /// Color of every boid of the species, the hue derived from the boid id when absent.
+    #[serde(default)]
+    pub color: Option<[f32; 3]>,
+}
+
+impl Species {
+    pub fn new(name: impl Into<String>, params: FlockParams) -> Self {
+        Self {
+            name: name.into(),
+            params,
+            color: None,
+        }
+    }
+
+    /// Checks the species can share a simulation, reacting to each other as `interactions` says.
+    pub fn validate_all(
+        species: &[Species],
+        interactions: &InteractionMatrix,
+    ) -> Result<(), SpeciesError> {
+        let Some(first) = species.first() else {
+            return Err(SpeciesError::Empty);
+        };
+        if interactions.species() != species.len() {
+            return Err(SpeciesError::Matrix {
+                species: species.len(),
+                columns: interactions.species(),
+            });
+        }
+        for s in species {
+            s.params.validate().map_err(|err| SpeciesError::Params {
+                species: s.name.clone(),
+                err,
+            })?;
+            if s.params.neighbours != first.params.neighbours {
+                return Err(SpeciesError::NeighbourMode {
+                    species: s.name.clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for Species {
+    fn default() -> Self {
+        Self::new("default", FlockParams::default())
+    }
+}
+
+/// How a boid steers with respect to a boid of another species.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Interaction {
+    /// Separation, alignement and cohesion, as within a species.
+    #[default]
+    Flock,
+    /// Separation and alignement.
+    Align,
+    /// Separation and cohesion.
+    Cohere,
+    /// Keep away from it as far as the cohesion radius.
+    Avoid,
+    /// No steering at all, the bodies still collide.
+    Ignore,
+}
+
+impl Interaction {
+    pub fn aligns(self) -> bool {
+        matches!(self, Interaction::Flock | Interaction::Align)
+    }
+
+    pub fn coheres(self) -> bool {
+        matches!(self, Interaction::Flock | Interaction::Cohere)
+    }
+
+    pub fn separates(self) -> bool {
+        matches!(
+            self,
+            Interaction::Flock | Interaction::Align | Interaction::Cohere
+        )
+    }
+
+    /// Whether the colors of the two boids blend.
+    pub fn blends(self) -> bool {
+        self.aligns() || self.coheres()
+    }
+}
+
+/// Square matrix, row `a` column `b` being how a boid of species `a` reacts to one of species `b`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "Vec<Vec<Interaction>>", into = "Vec<Vec<Interaction>>")]
+pub struct InteractionMatrix {
+    species: usize,
+    cells: Vec<Interaction>,
+}
+
+impl InteractionMatrix {
+    pub fn uniform(species: usize, interaction: Interaction) -> Self {
+        Self {
+            species,
+            cells: vec![interaction; species * species],
+        }
+    }
+
+    pub fn from_rows(rows: &[Vec<Interaction>]) -> Result<Self, SpeciesError> {
+        let species = rows.len();
+        if let Some(row) = rows.iter().find(|row| row.len() != species) {
+            return Err(SpeciesError::Matrix {
+                species,
+                columns: row.len(),
+            });
+        }
+        Ok(Self {
+            species,
+            cells: rows.concat(),
+        })
+    }
+
+    pub fn species(&self) -> usize {
+        self.species
+    }
+
+    pub fn rows(&self) -> Vec<Vec<Interaction>> {
+        self.cells
+            .chunks(self.species.max(1))
+            .map(<[Interaction]>::to_vec)
+            .collect()
+    }
+
+    #[inline]
+    pub fn get(&self, a: usize, b: usize) -> Interaction {
+        self.cells[a * self.species + b]
+    }
+
+    pub fn set(&mut self, a: usize, b: usize, interaction: Interaction) {
+        self.cells[a * self.species + b] = interaction;
+    }
+}
+
+impl TryFrom<Vec<Vec<Interaction>>> for InteractionMatrix {
+    type Error = SpeciesError;
+
+    fn try_from(rows: Vec<Vec<Interaction>>) -> Result<Self, Self::Error> {
+        Self::from_rows(&rows)
+    }
+}
+
+impl From<InteractionMatrix> for Vec<Vec<Interaction>> {
+    fn from(matrix: InteractionMatrix) -> Self {
+        matrix.rows()
+    }
+}
+
+#[derive(Debug)]
+pub enum SpeciesError {
+    Empty,
+    /// The matrix is not `species` by `species`.
+    Matrix {
+        species: usize,
+        columns: usize,
+    },
+    Params {
+        species: String,
+        err: ParamsError,
+    },
+    /// The neighbours are found the same way for every species.
+    NeighbourMode {
+        species: String,
+    },
+}
+
+impl fmt::Display for SpeciesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpeciesError::Empty => write!(f, "there must be at least one species"),
+            SpeciesError::Matrix { species, columns } => write!(
+                f,
+                "the interaction matrix must be {species} by {species}, got a row of {columns}"
+            ),
+            SpeciesError::Params { species, err } => write!(f, "species `{species}` : {err}"),
+            SpeciesError::NeighbourMode { species } => write!(
+                f,
+                "species `{species}` must find its neighbours the same way as the first species"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SpeciesError {}
@@ -0,0 +1,208 @@
+//! Steering rules shared by the 2D and 3D boids, generic over the vector type.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{math::Vector, params::FlockParams, world::World};
+
+/// Sums gathered over the neighbours of a boid by the separation, alignement and cohesion rules.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct Neighbourhood<V> {
+    separation: V,
+    alignement: V,
+    alignement_number: f32,
+    cohesion: V,
+    cohesion_number: f32,
+}
+
+impl<V: Vector> Neighbourhood<V> {
+    /// `heading` is the velocity of the perceiving boid, `to_other` the displacement to the neighbour.
+    pub fn perceive_separation(&mut self, params: &FlockParams, heading: V, to_other: V) {
+        if to_other.length_sq() < params.separation * params.separation
+            && sees_toward(heading, to_other, params.separation_fov)
+        {
+            self.separation -= to_other;
+        }
+    }
+
+    /// Separation from a neighbour to keep away from, reaching as far as cohesion.
+    pub fn perceive_avoidance(&mut self, params: &FlockParams, heading: V, to_other: V) {
+        if params.reaches(to_other.length_sq(), params.cohesion * params.cohesion)
+            && sees_toward(heading, to_other, params.separation_fov)
+        {
+            self.separation -= to_other;
+        }
+    }
+
+    pub fn perceive_alignement(
+        &mut self,
+        params: &FlockParams,
+        heading: V,
+        to_other: V,
+        other_velocity: V,
+    ) {
+        if params.reaches(to_other.length_sq(), params.alignement * params.cohesion)
+            && sees_toward(heading, to_other, params.alignement_fov)
+        {
+            self.alignement_number += 1.;
+            self.alignement += other_velocity;
+        }
+    }
+
+    pub fn perceive_cohesion(
+        &mut self,
+        params: &FlockParams,
+        heading: V,
+        position: V,
+        to_other: V,
+    ) {
+        if params.reaches(to_other.length_sq(), params.cohesion * params.cohesion)
+            && sees_toward(heading, to_other, params.cohesion_fov)
+        {
+            self.cohesion_number += 1.;
+            // the copy of the neighbour nearest to the boid
+            self.cohesion += position + to_other;
+        }
+    }
+
+    /// Number of neighbours taken into account by the cohesion rule.
+    pub fn neighbour_count(&self) -> u32 {
+        self.cohesion_number as u32
+    }
+
+    pub fn separation_acceleration(&self, params: &FlockParams) -> V {
+        self.separation * params.avoid_factor
+    }
+
+    pub fn alignement_acceleration(&self, params: &FlockParams, velocity: V) -> V {
+        if self.alignement_number > 0. {
+            let avg_vel = self.alignement / self.alignement_number;
+            (avg_vel - velocity) * params.matching_factor
+        } else {
+            V::default()
+        }
+    }
+
+    pub fn cohesion_acceleration(&self, params: &FlockParams, position: V) -> V {
+        if self.cohesion_number > 0. {
+            let avg_pos = self.cohesion / self.cohesion_number;
+            (avg_pos - position) * params.centering_factor
+        } else {
+            V::default()
+        }
+    }
+}
+
+/// Whether a point at `to_point` is inside the perception cone of full angle `fov`
+/// centered on `heading`. At rest, everything is seen.
+pub fn sees_toward<V: Vector>(heading: V, to_point: V, fov: f32) -> bool {
+    if fov >= std::f32::consts::TAU {
+        return true;
+    }
+
+    let norms = (heading.length_sq() * to_point.length_sq()).sqrt();
+    if norms <= 0. {
+        return true;
+    }
+    heading.dot(to_point) >= (fov * 0.5).cos() * norms
+}
+
+/// Turn rate, minimum and maximum speed limits applied to the velocity
+/// reached from `old_velocity` over a step of `dt`.
+pub fn constrain_velocity<V: Vector>(
+    params: &FlockParams,
+    old_velocity: V,
+    velocity: V,
+    dt: f32,
+) -> V {
+    let mut velocity = velocity;
+    if let Some(max_turn_rate) = params.max_turn_rate {
+        velocity = limit_turn(old_velocity, velocity, max_turn_rate * dt);
+    }
+
+    let speed = velocity.length();
+    if speed < params.min_speed {
+        if speed <= 0. {
+            velocity = V::splat(params.min_speed) * 2.;
+        } else {
+            velocity = velocity.normalized() * params.min_speed;
+        }
+    }
+    velocity.truncated(params.max_speed)
+}
+
+/// `new` rotated back toward `old` so that the heading turns by at most `max_angle`,
+/// keeping the norm of `new`.
+pub fn limit_turn<V: Vector>(old: V, new: V, max_angle: f32) -> V {
+    if old.length_sq() <= 0. || new.length_sq() <= 0. {
+        return new;
+    }
+
+    if old.angle_to(new) <= max_angle {
+        return new;
+    }
+    old.normalized().rotated_toward(new, max_angle) * new.length()
+}
+
+/// Stop on the edges of the world, steering back inside within `params.border_margin`.
+pub fn soft_turn<V: Vector>(
+    position: &mut V,
+    velocity: &mut V,
+    size: f32,
+    params: &FlockParams,
+    world: &World<V>,
+) {
+    for i in 0..V::DIM {
+        let bound = world.size[i];
+        if position[i] < size {
+            position[i] = size; // prevent sticking
+            velocity[i] = 0.;
+        } else if position[i] > bound - size {
+            position[i] = bound - size; // prevent sticking
+            velocity[i] = 0.;
+        }
+
+        if position[i] < params.border_margin {
+            velocity[i] += params.turn_factor;
+        }
+        if position[i] > bound - params.border_margin {
+            velocity[i] -= params.turn_factor;
+        }
+    }
+}
+
+/// Keep a ball of radius `size` inside the world, reflecting `velocity` on the edges.
+pub fn bounce<V: Vector>(position: &mut V, velocity: &mut V, size: f32, world: &World<V>) {
+    for i in 0..V::DIM {
+        if position[i] < size {
+            position[i] = size;
+            velocity[i] = velocity[i].abs();
+        } else if position[i] > world.size[i] - size {
+            position[i] = world.size[i] - size;
+            velocity[i] = -velocity[i].abs();
+        }
+    }
+}
+
+/// Half of the displacement pushing a boid out of a neighbour it overlaps,
+/// `away` going from the neighbour to the boid and `reach` being the sum of their sizes.
+/// The neighbour is pushed by the other half when it does the same.
+pub fn collision_push<V: Vector>(away: V, reach: f32) -> V {
+    if away.length_sq() >= reach * reach {
+        return V::default();
+    }
+    let dist = away.length().max(0.001);
+    let overlap = 0.5 * (reach - dist).max(0.);
+    away * overlap / dist
+}
+
+/// Sort the `neighbours` indices by position, then by index for the ties,
+/// so that sums over them do not depend on how the boids are stored.
+pub fn sort_by_position<V: Vector>(neighbours: &mut [usize], position: impl Fn(usize) -> V) {
+    neighbours.sort_unstable_by(|&a, &b| {
+        let (pa, pb) = (position(a), position(b));
+        (0..V::DIM)
+            .map(|i| pa[i].total_cmp(&pb[i]))
+            .find(|order| order.is_ne())
+            .unwrap_or_else(|| a.cmp(&b))
+    });
+}
@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+
+/// Fixed-step accumulator : frame times are cut into physics steps of `1 / hz`,
+/// the remainder being carried over to the next frame.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FixedTimestep {
+    hz: f32,
+    max_steps_per_frame: u32,
+    accumulator: f32,
+}
+
+impl Default for FixedTimestep {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_HZ, Self::DEFAULT_MAX_STEPS_PER_FRAME)
+    }
+}
+
+impl FixedTimestep {
+    pub const DEFAULT_HZ: f32 = 600.;
+    pub const DEFAULT_MAX_STEPS_PER_FRAME: u32 = 30;
+
+    /// `hz` must be finite and positive, `max_steps_per_frame` is at least 1.
+    pub fn new(hz: f32, max_steps_per_frame: u32) -> Self {
+        debug_assert!(hz.is_finite() && hz > 0.);
+        Self {
+            hz,
+            max_steps_per_frame: max_steps_per_frame.max(1),
+            accumulator: 0.,
+        }
+    }
+
+    pub fn hz(&self) -> f32 {
+        self.hz
+    }
+
+    pub fn max_steps_per_frame(&self) -> u32 {
+        self.max_steps_per_frame
+    }
+
+    pub fn dt(&self) -> f32 {
+        self.hz.recip()
+    }
+
+    /// Add `frame_dt` to the accumulator and return the number of physics steps to run.
+    /// When more than `max_steps_per_frame` steps are due, the extra time is dropped
+    /// so that a hitch slows the simulation down instead of making the next frames longer.
+    pub fn accumulate(&mut self, frame_dt: f32) -> u32 {
+        let dt = self.dt();
+        self.accumulator += frame_dt.max(0.);
+
+        let due = (self.accumulator / dt).floor();
+        self.accumulator = (self.accumulator - due * dt).max(0.);
+
+        (due as u32).min(self.max_steps_per_frame)
+    }
+
+    /// How far the current time is between the last two physics steps, in `[0, 1]`.
+    pub fn alpha(&self) -> f32 {
+        (self.accumulator * self.hz).clamp(0., 1.)
+    }
+}
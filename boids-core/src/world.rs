@@ -0,0 +1,129 @@
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    math::{Vec2, Vec3, Vector},
+    octree::Aabb3,
+    quadtree::Aabb,
+};
+
+/// How agents behave on the edges of the world.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Boundary {
+    /// Steer back inside within `border_margin`, stopping on the edges.
+    #[default]
+    SoftTurn,
+    /// Reflect the velocity on the edges.
+    Bounce,
+    /// Periodic world, leaving by an edge means coming back by the opposite one.
+    Wrap,
+}
+
+impl Boundary {
+    pub const ALL: [Boundary; 3] = [Boundary::SoftTurn, Boundary::Bounce, Boundary::Wrap];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Boundary::SoftTurn => "soft_turn",
+            Boundary::Bounce => "bounce",
+            Boundary::Wrap => "wrap",
+        }
+    }
+}
+
+impl fmt::Display for Boundary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Boundary {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.replace('-', "_");
+        Self::ALL
+            .into_iter()
+            .find(|boundary| boundary.name() == s)
+            .ok_or_else(|| {
+                let names: Vec<_> = Self::ALL.iter().map(|b| b.name()).collect();
+                format!(
+                    "unknown boundary `{s}`, expected one of {}",
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+/// Box from the origin to `size` the agents live in, a rectangle in 2D.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct World<V = Vec2> {
+    pub size: V,
+    pub boundary: Boundary,
+}
+
+impl World {
+    pub fn new(size: (f32, f32), boundary: Boundary) -> Self {
+        Self::with_size(size.into(), boundary)
+    }
+
+    pub fn bound(&self) -> Aabb {
+        Aabb::from_min_max(Vec2::zero(), self.size)
+    }
+}
+
+impl World<Vec3> {
+    pub fn bound(&self) -> Aabb3 {
+        Aabb3::from_min_max(Vec3::zero(), self.size)
+    }
+}
+
+impl<V: Vector> World<V> {
+    pub fn with_size(size: V, boundary: Boundary) -> Self {
+        Self { size, boundary }
+    }
+
+    pub fn wraps(&self) -> bool {
+        self.boundary == Boundary::Wrap
+    }
+
+    /// Shortest displacement from `from` to `to`, across the edges when the world wraps.
+    #[inline]
+    pub fn offset(&self, from: V, to: V) -> V {
+        let mut offset = to - from;
+        if self.wraps() {
+            for i in 0..V::DIM {
+                offset[i] -= self.size[i] * (offset[i] / self.size[i]).round();
+            }
+        }
+        offset
+    }
+
+    #[inline]
+    pub fn distance_sq(&self, a: V, b: V) -> f32 {
+        self.offset(a, b).length_sq()
+    }
+
+    /// `point` brought back inside the world when it wraps, unchanged otherwise.
+    pub fn wrap(&self, point: V) -> V {
+        if !self.wraps() {
+            return point;
+        }
+        let mut point = point;
+        for i in 0..V::DIM {
+            point[i] = point[i].rem_euclid(self.size[i]);
+            // rem_euclid rounds up to the size for tiny negative values
+            if point[i] >= self.size[i] {
+                point[i] = 0.;
+            }
+        }
+        point
+    }
+
+    /// Period of the world when it wraps, for the wrapped spatial queries.
+    pub fn period(&self) -> Option<V> {
+        self.wraps().then_some(self.size)
+    }
+}
@@ -0,0 +1,51 @@
+use boids_core::{Boid, Boids, Vec2, Vec3};
+
+fn flock() -> Vec<Boid> {
+    (0..5)
+        .map(|i| {
+            let mut boid = Boid::new((i as f32 * 10., 20.), i);
+            boid.velocity = Vec2::new(1., i as f32);
+            boid.previous_position = boid.position - boid.velocity;
+            boid.size = 1. + i as f32;
+            boid.set_species(i % 2);
+            boid.set_waypoint(i);
+            boid
+        })
+        .collect()
+}
+
+#[test]
+fn views_read_the_boids_pushed() {
+    let flock = flock();
+    let boids = Boids::from(flock.clone());
+    assert_eq!(boids.len(), flock.len());
+    assert!(boids.get(flock.len()).is_none());
+
+    for (view, boid) in boids.iter().zip(&flock) {
+        assert_eq!(view.position(), boid.position);
+        assert_eq!(view.velocity(), boid.velocity);
+        assert_eq!(
+            view.interpolated_position(0.5),
+            boid.interpolated_position(0.5)
+        );
+        assert_eq!(view.size(), boid.size);
+        assert_eq!(view.species(), boid.species());
+        assert_eq!(view.waypoint(), boid.waypoint());
+        assert_eq!(view.color(), boid.color());
+        assert_eq!(view.avg_color(), boid.avg_color());
+        assert_eq!(boids.positions()[view.index()], boid.position);
+    }
+}
+
+#[test]
+fn boids_convert_back_unchanged() {
+    let mut flock = flock();
+    flock[3].set_color(Vec3::new(0.1, 0.2, 0.3));
+    let back: Vec<Boid> = Boids::from(flock.clone()).into();
+    for (a, b) in back.iter().zip(&flock) {
+        assert_eq!(a.state(), b.state());
+        assert_eq!(a.previous_position, b.previous_position);
+        assert_eq!(a.color(), b.color());
+        assert_eq!((a.species(), a.waypoint()), (b.species(), b.waypoint()));
+    }
+}
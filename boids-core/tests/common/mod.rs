@@ -0,0 +1,20 @@
+//! Flocks shared by the integration tests.
+
+use boids_core::{
+    Scenario,
+    scenario::{InitialVelocity, SpawnDistribution},
+};
+
+/// `boid_count` boids spread over the whole world, heading anywhere at 20 to 100 px/s,
+/// to be adjusted through struct update syntax.
+pub fn scattered(boid_count: usize) -> Scenario {
+    Scenario {
+        boid_count,
+        spawn: SpawnDistribution::Uniform,
+        initial_velocity: InitialVelocity::Random {
+            min_speed: 20.,
+            max_speed: 100.,
+        },
+        ..Scenario::default()
+    }
+}
@@ -0,0 +1,204 @@
+mod common;
+
+use boids_core::{
+    Attractor, Boundary, NeighbourMode, Obstacle, Scenario, Simulation,
+    headless::{self, HeadlessParams},
+    rng::Rng,
+    snapshot::{self, SnapshotError},
+    spatial::SpatialIndexKind,
+};
+
+fn params(seed: u64) -> HeadlessParams {
+    HeadlessParams {
+        scenario: Scenario {
+            seed,
+            ..common::scattered(100)
+        },
+        steps: 600,
+    }
+}
+
+#[test]
+fn same_seed_gives_identical_trajectories() {
+    let a = headless::run(&params(42)).unwrap();
+    let b = headless::run(&params(42)).unwrap();
+
+    assert_eq!(a.step_count(), 600);
+    assert_eq!(a.state_hash(), b.state_hash());
+}
+
+#[test]
+fn different_seeds_diverge() {
+    let a = headless::run(&params(1)).unwrap();
+    let b = headless::run(&params(2)).unwrap();
+
+    assert_ne!(a.state_hash(), b.state_hash());
+}
+
+#[test]
+fn hash_is_stable_along_the_run() {
+    let mut hashes_a = Vec::new();
+    let mut hashes_b = Vec::new();
+    headless::run_with(&params(7), |_, sim| hashes_a.push(sim.state_hash())).unwrap();
+    headless::run_with(&params(7), |_, sim| hashes_b.push(sim.state_hash())).unwrap();
+
+    assert_eq!(hashes_a, hashes_b);
+}
+
+/// The same flock stepped twice, its boids stored in spawn order then in reverse order.
+fn run_forward_and_reversed(scenario: &Scenario, steps: u32) -> (Simulation, Simulation) {
+    let boids = scenario.spawn_boids(&mut Rng::new(scenario.seed));
+    let reversed = boids.iter().rev().cloned().collect();
+    let build = |boids| {
+        let mut sim =
+            Simulation::with_params(boids, scenario.world_size, scenario.params.clone()).unwrap();
+        sim.set_boundary(scenario.boundary);
+        for _ in 0..steps {
+            sim.step();
+        }
+        sim
+    };
+    (build(boids), build(reversed))
+}
+
+#[test]
+fn step_does_not_depend_on_boid_order() {
+    let mut scenario = params(5).scenario;
+    let topological = NeighbourMode::Topological { k: 7 };
+    for (neighbours, boundary) in [
+        (NeighbourMode::Metric, Boundary::SoftTurn),
+        (topological, Boundary::Wrap),
+    ] {
+        scenario.params.neighbours = neighbours;
+        scenario.boundary = boundary;
+        let (forward, reversed) = run_forward_and_reversed(&scenario, 300);
+
+        for (a, b) in forward.boids().iter().zip(reversed.boids().iter().rev()) {
+            assert_eq!(a.position(), b.position(), "{neighbours:?} {boundary}");
+            assert_eq!(a.velocity(), b.velocity(), "{neighbours:?} {boundary}");
+        }
+    }
+}
+
+#[test]
+fn spatial_indices_give_identical_trajectories() {
+    let mut params = params(13);
+    params.steps = 300;
+    let topological = NeighbourMode::Topological { k: 7 };
+    for (neighbours, boundary) in [
+        (NeighbourMode::Metric, Boundary::SoftTurn),
+        (topological, Boundary::Wrap),
+    ] {
+        params.scenario.params.neighbours = neighbours;
+        params.scenario.boundary = boundary;
+        let hashes: Vec<_> = SpatialIndexKind::ALL
+            .into_iter()
+            .map(|kind| {
+                params.scenario.spatial_index = kind;
+                headless::run(&params).unwrap().state_hash()
+            })
+            .collect();
+        assert!(
+            hashes.iter().all(|&hash| hash == hashes[0]),
+            "{neighbours:?} {boundary}"
+        );
+    }
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn parallel_step_matches_serial() {
+    let mut scenario = params(9).scenario;
+    scenario.boid_count = 500;
+    scenario.predator_count = 3;
+    let topological = NeighbourMode::Topological { k: 7 };
+    for (neighbours, boundary) in [
+        (NeighbourMode::Metric, Boundary::SoftTurn),
+        (topological, Boundary::Wrap),
+    ] {
+        scenario.params.neighbours = neighbours;
+        scenario.boundary = boundary;
+        let run = |parallel| {
+            let mut sim = scenario.build().unwrap();
+            sim.set_parallel(parallel);
+            headless::step_with(sim, 100, |_, _| ())
+        };
+        assert_eq!(
+            run(false).state_hash(),
+            run(true).state_hash(),
+            "{neighbours:?} {boundary}"
+        );
+    }
+
+    let run_3d = |parallel| {
+        let mut sim = params(9).scenario.build_3d().unwrap();
+        sim.set_parallel(parallel);
+        for _ in 0..100 {
+            sim.step();
+        }
+        sim.state_hash()
+    };
+    assert_eq!(run_3d(false), run_3d(true));
+}
+
+#[test]
+fn snapshot_resumes_bit_identical() {
+    let mut params = params(11);
+    params.scenario.predator_count = 2;
+    let full = headless::run(&params).unwrap();
+
+    params.steps = 250;
+    let first_half = headless::run(&params).unwrap();
+    let saved = snapshot::to_string(&first_half).unwrap();
+    let resumed = headless::step_with(snapshot::from_str(&saved).unwrap(), 350, |_, _| ());
+
+    assert_eq!(resumed.step_count(), full.step_count());
+    assert_eq!(resumed.time(), full.time());
+    assert_eq!(resumed.state_hash(), full.state_hash());
+}
+
+#[test]
+fn snapshot_of_another_version_is_rejected() {
+    let sim = headless::run(&params(3)).unwrap();
+    let saved = snapshot::to_string(&sim).unwrap();
+    let older = saved.replacen(&format!("version: {}", snapshot::VERSION), "version: 0", 1);
+
+    assert!(matches!(
+        snapshot::from_str(&older),
+        Err(SnapshotError::Version { found: 0, .. })
+    ));
+}
+
+#[test]
+fn snapshot_is_checked_like_a_scenario() {
+    let mut sim = Simulation::new(Vec::new(), (400., 300.));
+    sim.add_obstacle(Obstacle::circle((10., 10.), 5.)).unwrap();
+    sim.add_attractor(Attractor::new((20., 20.), 1., Some(3.)));
+    let saved = snapshot::to_string(&sim).unwrap();
+    assert!(snapshot::from_str(&saved).is_ok());
+
+    let edit = |from: &str, to: &str| {
+        assert!(saved.contains(from), "{from}");
+        snapshot::from_str(&saved.replacen(from, to, 1))
+    };
+    assert!(matches!(
+        edit("size: (400.0, 300.0)", "size: (0.0, 300.0)"),
+        Err(SnapshotError::Invalid(_))
+    ));
+    assert!(matches!(
+        edit("max_speed: 300.0", "max_speed: -300.0"),
+        Err(SnapshotError::Species(_))
+    ));
+    assert!(matches!(
+        edit("chase_factor: 4.0", "chase_factor: -4.0"),
+        Err(SnapshotError::Predators(_))
+    ));
+    assert!(matches!(
+        edit("radius: 5.0", "radius: -5.0"),
+        Err(SnapshotError::Obstacle(_))
+    ));
+    assert!(matches!(
+        edit("radius: Some(3.0)", "radius: Some(-3.0)"),
+        Err(SnapshotError::Goal(_))
+    ));
+}
@@ -0,0 +1,57 @@
+mod common;
+
+use boids_core::{
+    Boundary, Scenario, Vec2,
+    headless::{self, HeadlessParams},
+};
+
+const STEPS: u32 = 200;
+
+fn params(boundary: Boundary) -> HeadlessParams {
+    HeadlessParams {
+        scenario: Scenario {
+            boundary,
+            ..common::scattered(150)
+        },
+        steps: STEPS,
+    }
+}
+
+#[test]
+fn flock_stays_finite_and_inside_the_world() {
+    for boundary in [Boundary::Bounce, Boundary::Wrap] {
+        let params = params(boundary);
+        let mut steps = 0;
+        let sim = headless::run_with(&params, |step, _| steps = step).unwrap();
+        assert_eq!((steps, sim.step_count()), (STEPS, u64::from(STEPS)));
+        assert_eq!(sim.len(), params.scenario.boid_count);
+
+        // the boundary puts the boids back at the start of a step, they may then move
+        // up to a step at full speed past it, pushed a bit further by their neighbours
+        let margin = sim.params().max_speed * sim.timestep().dt() + 4.;
+        let size = Vec2::from(params.scenario.world_size);
+        for &position in sim.boids().positions() {
+            assert!(
+                position.as_array().iter().all(|x| x.is_finite()),
+                "{boundary} : {position:?}"
+            );
+            for i in 0..2 {
+                assert!(
+                    (-margin..=size[i] + margin).contains(&position[i]),
+                    "{boundary} : {position:?} outside of {size:?}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn flock_3d_stays_finite() {
+    let sim = headless::run_3d(&params(Boundary::Bounce)).unwrap();
+    assert_eq!(sim.step_count(), u64::from(STEPS));
+    assert_eq!(sim.len(), 150);
+    assert!(sim.boids().iter().all(|boid| {
+        boid.position.as_array().iter().all(|x| x.is_finite())
+            && boid.velocity.as_array().iter().all(|v| v.is_finite())
+    }));
+}
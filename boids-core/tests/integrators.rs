@@ -0,0 +1,112 @@
+mod common;
+
+use boids_core::{
+    Scenario, Vec2,
+    headless::{self, HeadlessParams},
+    integrator::Integrator,
+    scenario::InitialVelocity,
+};
+
+/// Energy of a unit harmonic oscillator, `a = -x`, started on a circular orbit of energy 1.
+fn oscillator_energy(integrator: Integrator, dt: f32, steps: u32) -> f32 {
+    let (mut x, mut v) = (Vec2::new(1., 0.), Vec2::new(0., 1.));
+    for _ in 0..steps {
+        (x, v) = integrator.integrate(x, v, dt, |x, _| -x);
+    }
+    0.5 * (x.length_sq() + v.length_sq())
+}
+
+/// Error at `t = 1` on the velocity damping `a = -v`, which is how alignement acts.
+fn damping_error(integrator: Integrator, dt: f32) -> f32 {
+    let (mut x, mut v) = (Vec2::zero(), Vec2::new(1., 0.));
+    for _ in 0..(1. / dt).round() as u32 {
+        (x, v) = integrator.integrate(x, v, dt, |_, v| -v);
+    }
+    (v[0] - (-1f32).exp()).abs()
+}
+
+#[test]
+fn energy_drift_on_oscillator() {
+    let drift = |integrator| (oscillator_energy(integrator, 0.01, 10_000) - 1.).abs();
+    let euler = drift(Integrator::ExplicitEuler);
+    assert!(euler > 0.5, "explicit euler : drift {euler}");
+    for (integrator, tolerance) in [
+        (Integrator::SemiImplicitEuler, 0.02),
+        (Integrator::Verlet, 0.01),
+        (Integrator::Rk4, 1e-3),
+    ] {
+        let drift = drift(integrator);
+        assert!(drift < tolerance, "{integrator} : drift {drift}");
+    }
+}
+
+#[test]
+fn stability_at_large_dt() {
+    let energy = |integrator| oscillator_energy(integrator, 1., 1000);
+    let euler = energy(Integrator::ExplicitEuler);
+    assert!(
+        !euler.is_finite() || euler > 1e6,
+        "explicit euler : energy {euler}"
+    );
+    // the others do not blow up, RK4 damping the oscillation
+    for integrator in [
+        Integrator::SemiImplicitEuler,
+        Integrator::Verlet,
+        Integrator::Rk4,
+    ] {
+        let energy = energy(integrator);
+        assert!(energy < 2., "{integrator} : energy {energy}");
+    }
+}
+
+#[test]
+fn accuracy_order_on_damping() {
+    let [semi_implicit, verlet, rk4] = [
+        Integrator::SemiImplicitEuler,
+        Integrator::Verlet,
+        Integrator::Rk4,
+    ]
+    .map(|integrator| damping_error(integrator, 0.1));
+    assert!(
+        rk4 < verlet && verlet < semi_implicit,
+        "errors : rk4 {rk4}, verlet {verlet}, semi-implicit euler {semi_implicit}"
+    );
+    assert!(rk4 < 1e-5, "rk4 : error {rk4}");
+}
+
+#[test]
+fn flock_stays_finite_at_large_dt() {
+    for integrator in Integrator::ALL {
+        let params = HeadlessParams {
+            scenario: Scenario {
+                physics_hz: 20.,
+                integrator,
+                initial_velocity: InitialVelocity::Random {
+                    min_speed: 50.,
+                    max_speed: 200.,
+                },
+                ..common::scattered(100)
+            },
+            steps: 500,
+        };
+        let sim = headless::run(&params).unwrap();
+
+        let finite = sim.boids().iter().all(|boid| {
+            boid.position().as_array().iter().all(|x| x.is_finite())
+                && boid.velocity().as_array().iter().all(|v| v.is_finite())
+        });
+        assert!(finite, "{integrator} produced a non finite state");
+    }
+}
+
+#[test]
+fn parse_integrator_names() {
+    for integrator in Integrator::ALL {
+        assert_eq!(integrator.name().parse(), Ok(integrator));
+    }
+    assert_eq!(
+        "semi-implicit-euler".parse(),
+        Ok(Integrator::SemiImplicitEuler)
+    );
+    assert!("leapfrog".parse::<Integrator>().is_err());
+}
@@ -0,0 +1,122 @@
+use std::f32::consts::{FRAC_1_SQRT_2, FRAC_PI_2, FRAC_PI_4, PI};
+
+use boids_core::{
+    Boundary, FlockParams, Scenario, Vec2, Vec3,
+    steering::{constrain_velocity, limit_turn},
+};
+
+mod common;
+
+const EPSILON: f32 = 1e-4;
+
+fn assert_close(found: Vec2, expected: Vec2) {
+    assert!(
+        (found - expected).length() < EPSILON,
+        "expected {expected:?}, got {found:?}"
+    );
+}
+
+#[test]
+fn vec2_rotates_toward_either_side() {
+    let x = Vec2::new(1., 0.);
+    assert_close(
+        x.rotated_toward(Vec2::new(0., 1.), FRAC_PI_4),
+        Vec2::new(FRAC_1_SQRT_2, FRAC_1_SQRT_2),
+    );
+    assert_close(
+        x.rotated_toward(Vec2::new(0., -3.), FRAC_PI_4),
+        Vec2::new(FRAC_1_SQRT_2, -FRAC_1_SQRT_2),
+    );
+}
+
+#[test]
+fn vec3_rotates_in_the_plane_toward_the_other() {
+    let x = Vec3::new(1., 0., 0.);
+    let rotated = x.rotated_toward(Vec3::new(0., 0., 2.), FRAC_PI_2);
+    assert!(
+        (rotated - Vec3::new(0., 0., 1.)).length() < EPSILON,
+        "{rotated:?}"
+    );
+
+    // opposite vectors span no plane, any perpendicular turn will do
+    let rotated = x.rotated_toward(-x, FRAC_PI_2);
+    assert!((rotated.length() - 1.).abs() < EPSILON, "{rotated:?}");
+    assert!(rotated.dot(x).abs() < EPSILON, "{rotated:?}");
+}
+
+#[test]
+fn limit_turn_clamps_the_angle_and_keeps_the_norm() {
+    let old = Vec2::new(10., 0.);
+    let new = Vec2::new(0., 50.);
+    let limited = limit_turn(old, new, FRAC_PI_4);
+    assert!((old.angle_to(limited) - FRAC_PI_4).abs() < EPSILON);
+    assert!((limited.length() - 50.).abs() < EPSILON);
+
+    // smaller turns are left alone
+    assert_eq!(limit_turn(old, new, PI), new);
+    assert_eq!(limit_turn(Vec2::default(), new, 0.), new);
+}
+
+#[test]
+fn constrained_speed_stays_within_the_limits() {
+    let params = FlockParams::default();
+    let old = Vec2::new(100., 0.);
+    for velocity in [
+        Vec2::default(),
+        Vec2::new(1., 0.),
+        Vec2::new(0., 120.),
+        Vec2::new(-5000., 5000.),
+    ] {
+        let speed = constrain_velocity(&params, old, velocity, 0.1).length();
+        assert!(
+            speed >= params.min_speed - EPSILON && speed <= params.max_speed + EPSILON,
+            "{velocity:?} : speed {speed}"
+        );
+    }
+}
+
+#[test]
+fn constrained_turn_stays_within_the_turn_rate() {
+    let params = FlockParams {
+        max_turn_rate: Some(PI),
+        ..FlockParams::default()
+    };
+    let dt = 0.1;
+    let old = Vec2::new(100., 0.);
+    let constrained = constrain_velocity(&params, old, Vec2::new(-100., 10.), dt);
+    assert!((old.angle_to(constrained) - PI * dt).abs() < EPSILON);
+
+    let slight = Vec2::new(100., 10.);
+    assert_eq!(constrain_velocity(&params, old, slight, dt), slight);
+}
+
+#[test]
+fn flock_respects_the_limits_at_every_step() {
+    let params = FlockParams {
+        max_turn_rate: Some(2.),
+        ..FlockParams::default()
+    };
+    // no walls to bounce on, only the steering turns the boids
+    let mut sim = Scenario {
+        boundary: Boundary::Wrap,
+        params: params.clone(),
+        ..common::scattered(200)
+    }
+    .build()
+    .unwrap();
+    let max_angle = 2. * sim.timestep().dt();
+
+    for _ in 0..50 {
+        let before = sim.boids().velocities().to_vec();
+        sim.step();
+        for (old, new) in before.iter().zip(sim.boids().velocities()) {
+            let speed = new.length();
+            assert!(
+                speed >= params.min_speed - EPSILON && speed <= params.max_speed + EPSILON,
+                "speed {speed}"
+            );
+            let angle = old.angle_to(*new);
+            assert!(angle <= max_angle + EPSILON, "turned {angle} > {max_angle}");
+        }
+    }
+}
@@ -0,0 +1,144 @@
+use boids_core::{
+    Scenario, Simulation, Vec2,
+    obstacle::{Obstacle, ObstacleError},
+    scenario::ScenarioError,
+};
+
+fn assert_close(found: (f32, Vec2), expected: (f32, [f32; 2])) {
+    let (dist, normal) = found;
+    assert!(
+        (dist - expected.0).abs() < 1e-4 && (normal - Vec2::from(expected.1)).length() < 1e-4,
+        "expected {expected:?}, got ({dist}, {normal:?})"
+    );
+}
+
+#[test]
+fn circle_signed_distance() {
+    let circle = Obstacle::circle((10., 10.), 5.);
+    assert_close(circle.signed_distance(Vec2::new(20., 10.)), (5., [1., 0.]));
+    assert_close(circle.signed_distance(Vec2::new(10., 12.)), (-3., [0., 1.]));
+    // the center has no direction out, any unit normal will do
+    let (dist, normal) = circle.signed_distance(Vec2::new(10., 10.));
+    assert_eq!(dist, -5.);
+    assert!((normal.length() - 1.).abs() < 1e-4);
+}
+
+#[test]
+fn box_signed_distance() {
+    let square = Obstacle::square((0., 0.), 10.);
+    assert_close(square.signed_distance(Vec2::new(15., 0.)), (5., [1., 0.]));
+    assert_close(square.signed_distance(Vec2::new(0., -13.)), (3., [0., -1.]));
+    assert_close(
+        square.signed_distance(Vec2::new(13., 14.)),
+        (5., [0.6, 0.8]),
+    );
+    // inside, out through the nearest side
+    assert_close(square.signed_distance(Vec2::new(-8., 1.)), (-2., [-1., 0.]));
+    assert_close(square.signed_distance(Vec2::new(3., 9.)), (-1., [0., 1.]));
+}
+
+#[test]
+fn polygon_signed_distance() {
+    let triangle = Obstacle::Polygon {
+        points: vec![Vec2::new(0., 0.), Vec2::new(10., 0.), Vec2::new(0., 10.)],
+    };
+    assert_close(
+        triangle.signed_distance(Vec2::new(5., -4.)),
+        (4., [0., -1.]),
+    );
+    assert_close(
+        triangle.signed_distance(Vec2::new(-2., 5.)),
+        (2., [-1., 0.]),
+    );
+    let diagonal = std::f32::consts::FRAC_1_SQRT_2;
+    assert_close(
+        triangle.signed_distance(Vec2::new(6., 6.)),
+        (2f32.sqrt(), [diagonal, diagonal]),
+    );
+    // inside, the normal still points out
+    assert_close(
+        triangle.signed_distance(Vec2::new(1., 4.)),
+        (-1., [-1., 0.]),
+    );
+    assert_close(
+        triangle.signed_distance(Vec2::new(4., 1.)),
+        (-1., [0., -1.]),
+    );
+}
+
+#[test]
+fn valid_shapes_are_accepted() {
+    assert_eq!(Obstacle::circle((0., 0.), 0.).validate(), Ok(()));
+    assert_eq!(Obstacle::square((0., 0.), 5.).validate(), Ok(()));
+    assert_eq!(
+        Obstacle::regular_polygon((0., 0.), 5., 3).validate(),
+        Ok(())
+    );
+}
+
+#[test]
+fn invalid_shapes_are_rejected() {
+    let point = Vec2::new(1., 1.);
+    let cases = [
+        (
+            Obstacle::circle((0., 0.), -1.),
+            ObstacleError::NegativeRadius { radius: -1. },
+        ),
+        (
+            Obstacle::circle((f32::NAN, 0.), 1.),
+            ObstacleError::NotFinite,
+        ),
+        (
+            Obstacle::Box {
+                min: Vec2::new(10., 0.),
+                max: Vec2::new(0., 10.),
+            },
+            ObstacleError::InvertedBox {
+                min: Vec2::new(10., 0.),
+                max: Vec2::new(0., 10.),
+            },
+        ),
+        (
+            Obstacle::Polygon {
+                points: vec![point, Vec2::new(2., 2.)],
+            },
+            ObstacleError::TooFewPoints { count: 2 },
+        ),
+        (
+            Obstacle::Polygon {
+                points: vec![point, point, Vec2::new(2., 0.)],
+            },
+            ObstacleError::DegenerateEdge { index: 0 },
+        ),
+        (
+            Obstacle::Polygon {
+                points: vec![point, Vec2::new(2., 0.), Vec2::new(0., 2.), point],
+            },
+            ObstacleError::DegenerateEdge { index: 3 },
+        ),
+    ];
+    for (obstacle, expected) in cases {
+        assert_eq!(obstacle.validate(), Err(expected), "{obstacle:?}");
+    }
+}
+
+#[test]
+fn invalid_obstacles_are_not_added() {
+    let inverted = Obstacle::Box {
+        min: Vec2::new(10., 10.),
+        max: Vec2::new(0., 0.),
+    };
+
+    let mut sim = Simulation::new(Vec::new(), (100., 100.));
+    assert!(sim.add_obstacle(inverted.clone()).is_err());
+    assert!(sim.obstacles().is_empty());
+
+    let scenario = Scenario {
+        obstacles: vec![inverted],
+        ..Scenario::default()
+    };
+    assert!(matches!(
+        scenario.validate(),
+        Err(ScenarioError::Obstacle(ObstacleError::InvertedBox { .. }))
+    ));
+}
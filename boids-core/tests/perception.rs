@@ -0,0 +1,201 @@
+mod common;
+
+use std::f32::consts::{PI, TAU};
+
+use boids_core::{
+    Boid, Boundary, FlockParams, Interaction, NeighbourMode, Obstacle, Scenario, Simulation, Vec2,
+    World, boid::Steering, params::ParamsError,
+};
+
+/// `leader` heading right with `follower` right behind it, heading right too.
+fn leader_and_follower() -> (Boid, Boid) {
+    let mut leader = Boid::new((100., 100.), 0);
+    leader.velocity = Vec2::new(50., 0.);
+    let mut follower = Boid::new((80., 100.), 1);
+    follower.velocity = Vec2::new(50., 0.);
+    (leader, follower)
+}
+
+fn world() -> World {
+    World::new((800., 600.), Boundary::SoftTurn)
+}
+
+/// What `me` gathers from `other` through every rule.
+fn perceived(params: &FlockParams, me: &Boid, other: &Boid) -> Steering {
+    let mut steering = Steering::default();
+    steering.perceive(
+        params,
+        &world(),
+        Interaction::Flock,
+        me.state(),
+        other.state(),
+    );
+    steering
+}
+
+fn params_with_fov(fov: f32) -> FlockParams {
+    FlockParams {
+        separation: 30.,
+        separation_fov: fov,
+        alignement_fov: fov,
+        cohesion_fov: fov,
+        ..FlockParams::default()
+    }
+}
+
+#[test]
+fn full_fov_sees_all_around() {
+    let (leader, follower) = leader_and_follower();
+    assert!(leader.sees(follower.position, TAU));
+    assert!(follower.sees(leader.position, TAU));
+}
+
+#[test]
+fn half_fov_ignores_what_is_behind() {
+    let (leader, follower) = leader_and_follower();
+    assert!(!leader.sees(follower.position, PI));
+    assert!(follower.sees(leader.position, PI));
+}
+
+#[test]
+fn cone_edges() {
+    let (leader, _) = leader_and_follower();
+    let side = leader.position + Vec2::new(0., 10.);
+    let ahead_left = leader.position + Vec2::new(10., 10.);
+
+    assert!(leader.sees(ahead_left, PI / 2. + 0.01));
+    assert!(!leader.sees(ahead_left, PI / 2. - 0.01));
+    assert!(leader.sees(side, PI + 0.01));
+    assert!(!leader.sees(side, PI - 0.01));
+}
+
+#[test]
+fn boid_at_rest_sees_all_around() {
+    let (mut leader, follower) = leader_and_follower();
+    leader.velocity = Vec2::zero();
+    assert!(leader.sees(follower.position, 0.));
+}
+
+#[test]
+fn cohesion_only_pulls_the_boid_that_sees() {
+    let params = params_with_fov(PI);
+    let (leader, follower) = leader_and_follower();
+
+    let by_leader = perceived(&params, &leader, &follower);
+    assert_eq!(
+        by_leader.cohesion_acceleration(&params, leader.position),
+        Vec2::zero()
+    );
+    let by_follower = perceived(&params, &follower, &leader);
+    assert!(by_follower.cohesion_acceleration(&params, follower.position)[0] > 0.);
+}
+
+#[test]
+fn alignement_only_steers_the_boid_that_sees() {
+    let params = params_with_fov(PI);
+    let (leader, mut follower) = leader_and_follower();
+    follower.velocity = Vec2::new(50., 20.);
+
+    let by_leader = perceived(&params, &leader, &follower);
+    assert_eq!(
+        by_leader.alignement_acceleration(&params, leader.velocity),
+        Vec2::zero()
+    );
+    let by_follower = perceived(&params, &follower, &leader);
+    assert!(by_follower.alignement_acceleration(&params, follower.velocity)[1] < 0.);
+}
+
+#[test]
+fn separation_only_pushes_the_boid_that_sees() {
+    let params = params_with_fov(PI);
+    let (leader, follower) = leader_and_follower();
+
+    let by_leader = perceived(&params, &leader, &follower);
+    assert_eq!(by_leader.separation_acceleration(&params), Vec2::zero());
+    let by_follower = perceived(&params, &follower, &leader);
+    assert!(by_follower.separation_acceleration(&params)[0] < 0.);
+}
+
+#[test]
+fn full_fov_is_symmetric() {
+    let params = params_with_fov(TAU);
+    let (leader, follower) = leader_and_follower();
+
+    let by_leader = perceived(&params, &leader, &follower);
+    assert!(by_leader.cohesion_acceleration(&params, leader.position)[0] < 0.);
+    let by_follower = perceived(&params, &follower, &leader);
+    assert!(by_follower.cohesion_acceleration(&params, follower.position)[0] > 0.);
+}
+
+#[test]
+fn fov_above_tau_is_rejected() {
+    let params = params_with_fov(7.);
+    assert!(params.validate().is_err());
+}
+
+#[test]
+fn topological_mode_feeds_the_k_nearest() {
+    let count = 6;
+    for k in [1, 3, count - 1, count, 20] {
+        for boundary in [Boundary::SoftTurn, Boundary::Wrap] {
+            let mut scenario = Scenario {
+                boundary,
+                ..common::scattered(count)
+            };
+            scenario.params.neighbours = NeighbourMode::Topological { k };
+            let mut sim = scenario.build().unwrap();
+            sim.step();
+
+            for boid in sim.boids().iter() {
+                assert_eq!(boid.neighbour_count() as usize, k.min(count - 1), "k = {k}");
+            }
+        }
+    }
+}
+
+#[test]
+fn neighbour_count_out_of_range_is_rejected() {
+    for k in [0, NeighbourMode::MAX_K + 1, usize::MAX] {
+        let params = FlockParams {
+            neighbours: NeighbourMode::Topological { k },
+            ..FlockParams::default()
+        };
+        assert_eq!(params.validate(), Err(ParamsError::NeighbourCount { k }));
+    }
+}
+
+#[test]
+fn obstacles_are_felt_across_the_wrapping_edges() {
+    let params = FlockParams::default();
+    // right of the boid through the right edge
+    let obstacles = [Obstacle::circle((2., 300.), 6.)];
+    let position = Vec2::new(795., 300.);
+
+    let mut steering = Steering::default();
+    let wrapping = World::new((800., 600.), Boundary::Wrap);
+    steering.perceive_obstacles(&params, &wrapping, position, 2., &obstacles);
+    let push = steering.obstacle_acceleration(&params);
+    assert!(push.x() < 0. && push.y() == 0., "{push:?}");
+
+    let mut steering = Steering::default();
+    steering.perceive_obstacles(&params, &world(), position, 2., &obstacles);
+    assert_eq!(steering.obstacle_acceleration(&params), Vec2::default());
+}
+
+#[test]
+fn boids_collide_with_obstacles_across_the_wrapping_edges() {
+    let mut boid = Boid::new((785., 300.), 0);
+    boid.velocity = Vec2::new(300., 0.);
+    let size = boid.size;
+    let obstacle = Obstacle::circle((2., 300.), 6.);
+
+    let mut sim = Simulation::new(vec![boid], (800., 600.));
+    sim.set_boundary(Boundary::Wrap);
+    sim.add_obstacle(obstacle).unwrap();
+    for _ in 0..30 {
+        sim.step();
+        let position = sim.boids().positions()[0];
+        let dist = sim.world().offset(Vec2::new(2., 300.), position).length();
+        assert!(dist >= 6. + size - 1e-3, "{position:?} at {dist}");
+    }
+}
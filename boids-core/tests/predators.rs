@@ -0,0 +1,83 @@
+use boids_core::{
+    Boid, Boundary, FlockParams, Scenario, Simulation, Vec2, World,
+    boid::Steering,
+    predator::PredatorParams,
+    scenario::ScenarioError,
+    snapshot::{self, SnapshotError},
+};
+
+/// Still boids at `positions` and a predator at `predator`, after a step.
+fn stepped(positions: &[(f32, f32)], predator: (f32, f32), boundary: Boundary) -> Simulation {
+    let boids = positions
+        .iter()
+        .enumerate()
+        .map(|(i, &pos)| Boid::new(pos, i))
+        .collect();
+    let mut sim = Simulation::new(boids, (400., 400.));
+    sim.set_boundary(boundary);
+    sim.add_predator(predator);
+    sim.step();
+    sim
+}
+
+#[test]
+fn predator_chases_the_nearest_boid() {
+    let sim = stepped(
+        &[(100., 200.), (300., 200.)],
+        (150., 200.),
+        Boundary::Bounce,
+    );
+    let velocity = sim.predators()[0].velocity;
+    assert!(velocity.x() < 0. && velocity.y() == 0., "{velocity:?}");
+
+    // the nearest through the edges of a wrapping world
+    let sim = stepped(&[(10., 200.), (300., 200.)], (390., 200.), Boundary::Wrap);
+    let velocity = sim.predators()[0].velocity;
+    assert!(velocity.x() > 0. && velocity.y() == 0., "{velocity:?}");
+}
+
+#[test]
+fn boids_flee_the_predator_within_the_flee_radius() {
+    let params = FlockParams::default();
+    let world = World::new((400., 400.), Boundary::Bounce);
+    let position = Vec2::new(200., 200.);
+
+    let mut steering = Steering::default();
+    steering.perceive_predator(&params, &world, position, Vec2::new(170., 200.));
+    let flee = steering.flee_acceleration(&params);
+    assert!(flee.x() > 0. && flee.y() == 0., "{flee:?}");
+
+    let mut steering = Steering::default();
+    let far = position - Vec2::new(params.flee_radius, 0.);
+    steering.perceive_predator(&params, &world, position, far);
+    assert_eq!(steering.flee_acceleration(&params), Vec2::default());
+
+    let sim = stepped(&[(200., 200.)], (170., 200.), Boundary::Bounce);
+    let velocity = sim.boids().velocities()[0];
+    assert!(velocity.x() > 0., "{velocity:?}");
+}
+
+#[test]
+fn invalid_predator_params_are_rejected() {
+    let invalid = PredatorParams {
+        max_speed: -1.,
+        ..PredatorParams::default()
+    };
+
+    let scenario = Scenario {
+        predator_params: invalid.clone(),
+        ..Scenario::default()
+    };
+    assert!(matches!(
+        scenario.validate(),
+        Err(ScenarioError::Predators(_))
+    ));
+
+    let mut sim = Simulation::new(Vec::new(), (400., 400.));
+    sim.set_predator_params(invalid);
+    let saved = snapshot::to_string(&sim).unwrap();
+    assert!(matches!(
+        snapshot::from_str(&saved),
+        Err(SnapshotError::Predators(_))
+    ));
+}
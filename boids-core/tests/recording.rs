@@ -0,0 +1,246 @@
+use std::path::PathBuf;
+
+use boids_core::{
+    Scenario, Simulation, Vec2,
+    recorder::{FORMAT_VERSION, Frame, MAGIC, RecordFormat, Recorder, WhenFull},
+    replay::{Replay, ReplayError},
+    simulation::spawn_spiral,
+};
+
+/// A file in the temporary directory, unique to the test and to the test run.
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("boids-{}-{name}", std::process::id()))
+}
+
+fn simulation() -> Simulation {
+    Scenario {
+        boid_count: 40,
+        ..Scenario::default()
+    }
+    .build()
+    .unwrap()
+}
+
+/// Step `sim` `steps` times, recording after each step,
+/// and return the frames the recorder should have written.
+fn record(sim: &mut Simulation, recorder: &mut Recorder, steps: u32, every: u64) -> Vec<Frame> {
+    let mut expected = Vec::new();
+    for _ in 0..steps {
+        sim.step();
+        recorder.record(sim);
+        if sim.step_count().is_multiple_of(every) {
+            expected.push(Frame::capture(sim));
+        }
+    }
+    expected
+}
+
+#[test]
+fn recordings_load_back_as_recorded() {
+    for format in RecordFormat::ALL {
+        let path = temp_path(&format!("round-trip.{format}"));
+        let mut recorder = Recorder::create(&path, format, 1, WhenFull::Block).unwrap();
+        let expected = record(&mut simulation(), &mut recorder, 5, 1);
+        let summary = recorder.finish().unwrap();
+        assert_eq!((summary.recorded, summary.skipped), (5, 0));
+
+        let replay = Replay::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(replay.frames(), expected, "{format}");
+    }
+}
+
+#[test]
+fn only_every_few_steps_are_recorded() {
+    let path = temp_path("every.binary");
+    let mut recorder = Recorder::create(&path, RecordFormat::Binary, 3, WhenFull::Block).unwrap();
+    let expected = record(&mut simulation(), &mut recorder, 10, 3);
+    assert_eq!(recorder.finish().unwrap().recorded, 3);
+
+    let replay = Replay::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    let steps: Vec<_> = replay.frames().iter().map(|frame| frame.step).collect();
+    assert_eq!(steps, [3, 6, 9]);
+    assert_eq!(replay.frames(), expected);
+}
+
+#[test]
+fn full_recorder_skips_frames() {
+    // a frame of a large flock takes far longer to write as CSV than to capture,
+    // the writer falls behind and the recorder drops frames instead of waiting
+    let sim = Simulation::new(spawn_spiral(2000, (800., 600.)), (800., 600.));
+    let path = temp_path("skip.csv");
+    let mut recorder = Recorder::create(&path, RecordFormat::Csv, 1, WhenFull::Skip).unwrap();
+    let attempts = 4 * Recorder::CAPACITY as u64;
+    for _ in 0..attempts {
+        recorder.record(&sim);
+    }
+    let summary = recorder.finish().unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(summary.skipped > 0, "{summary:?}");
+    assert!(summary.recorded >= Recorder::CAPACITY as u64, "{summary:?}");
+    assert_eq!(summary.recorded + summary.skipped, attempts);
+}
+
+/// Frames of a single boid, one every 0.1 s.
+fn replay(count: usize) -> Replay {
+    let frames = (0..count)
+        .map(|i| Frame {
+            step: i as u64,
+            time: i as f64 * 0.1,
+            positions: vec![Vec2::new(i as f32, 0.)],
+            velocities: vec![Vec2::new(1., 0.)],
+            species: vec![0],
+            neighbours: vec![0],
+        })
+        .collect();
+    Replay::new(frames).unwrap()
+}
+
+/// Replay of the file `name` holding `content`.
+fn load(name: &str, content: &[u8]) -> Result<Replay, ReplayError> {
+    let path = temp_path(name);
+    std::fs::write(&path, content).unwrap();
+    let replay = Replay::load(&path);
+    std::fs::remove_file(&path).unwrap();
+    replay
+}
+
+#[test]
+fn advance_shows_the_last_frame_before_the_playback_time() {
+    let mut replay = replay(4);
+    replay.advance(0.15);
+    assert_eq!(replay.index(), 1);
+
+    replay.set_speed(2.);
+    replay.advance(0.05);
+    assert_eq!(replay.index(), 2);
+    assert!(!replay.is_paused());
+
+    // pauses on the last frame
+    replay.advance(1.);
+    assert_eq!(replay.index(), 3);
+    assert!(replay.is_paused());
+    replay.advance(1.);
+    assert_eq!(replay.index(), 3);
+}
+
+#[test]
+fn speed_is_clamped_and_must_be_finite() {
+    let mut replay = replay(2);
+    replay.set_speed(1000.);
+    assert_eq!(replay.speed(), Replay::MAX_SPEED);
+    replay.set_speed(-1.);
+    assert_eq!(replay.speed(), Replay::MIN_SPEED);
+
+    replay.set_speed(2.);
+    for speed in [f32::NAN, f32::INFINITY, f32::NEG_INFINITY] {
+        replay.set_speed(speed);
+        assert_eq!(replay.speed(), 2., "{speed}");
+    }
+}
+
+#[test]
+fn step_by_pauses_and_stays_in_the_recording() {
+    let mut replay = replay(4);
+    replay.step_by(2);
+    assert_eq!(replay.index(), 2);
+    assert!(replay.is_paused());
+    replay.step_by(10);
+    assert_eq!(replay.index(), 3);
+    replay.step_by(-10);
+    assert_eq!(replay.index(), 0);
+
+    // playback goes on from the frame stepped to
+    replay.step_by(1);
+    replay.set_paused(false);
+    replay.advance(0.1);
+    assert_eq!(replay.index(), 2);
+}
+
+#[test]
+fn seek_goes_to_a_fraction_of_the_recording() {
+    let mut replay = replay(5);
+    replay.seek(0.5);
+    assert_eq!((replay.index(), replay.progress()), (2, 0.5));
+    replay.seek(2.);
+    assert_eq!(replay.index(), 4);
+    replay.seek(-1.);
+    assert_eq!(replay.index(), 0);
+}
+
+#[test]
+fn resuming_at_the_end_starts_over() {
+    let mut replay = replay(3);
+    replay.advance(1.);
+    assert!(replay.is_paused());
+    replay.set_paused(false);
+    assert_eq!(replay.index(), 0);
+    assert!(!replay.is_paused());
+
+    // anywhere else, it resumes where it is
+    replay.step_by(1);
+    replay.set_paused(false);
+    assert_eq!(replay.index(), 1);
+}
+
+#[test]
+fn csv_rows_must_be_in_boid_order() {
+    let header = "step,time,boid,x,y,vx,vy,species,neighbours\n";
+    let in_order = format!("{header}0,0,0,1,2,3,4,0,1\n0,0,1,5,6,7,8,1,0\n1,0.1,0,1,2,3,4,0,1\n");
+    let replay = load("in-order.csv", in_order.as_bytes()).unwrap();
+    assert_eq!(replay.len(), 2);
+    assert_eq!(
+        replay.frames()[0].positions,
+        [Vec2::new(1., 2.), Vec2::new(5., 6.)]
+    );
+
+    for rows in [
+        "0,0,1,1,2,3,4,0,1\n0,0,0,5,6,7,8,1,0\n",
+        "0,0,0,1,2,3,4,0,1\n0,0,2,5,6,7,8,1,0\n",
+    ] {
+        let content = format!("{header}{rows}");
+        assert!(
+            matches!(
+                load("out-of-order.csv", content.as_bytes()),
+                Err(ReplayError::Format(_))
+            ),
+            "{rows}"
+        );
+    }
+}
+
+#[test]
+fn truncated_binary_recordings_are_rejected() {
+    let path = temp_path("truncated.binary");
+    let mut recorder = Recorder::create(&path, RecordFormat::Binary, 1, WhenFull::Block).unwrap();
+    record(&mut simulation(), &mut recorder, 2, 1);
+    recorder.finish().unwrap();
+    let bytes = std::fs::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let truncated = load("truncated.binary", &bytes[..bytes.len() - 1]);
+    assert!(matches!(truncated, Err(ReplayError::Format(_))));
+
+    // a boid count far beyond the end of the file
+    let mut huge = MAGIC.to_vec();
+    huge.extend(FORMAT_VERSION.to_le_bytes());
+    huge.extend(0u64.to_le_bytes());
+    huge.extend(0f64.to_le_bytes());
+    huge.extend(u32::MAX.to_le_bytes());
+    assert!(matches!(
+        load("huge.binary", &huge),
+        Err(ReplayError::Format(_))
+    ));
+}
+
+#[test]
+fn other_format_versions_are_rejected() {
+    let mut bytes = MAGIC.to_vec();
+    bytes.extend((FORMAT_VERSION + 1).to_le_bytes());
+    assert!(matches!(
+        load("version.binary", &bytes),
+        Err(ReplayError::Version { found, expected }) if found == FORMAT_VERSION + 1 && expected == FORMAT_VERSION
+    ));
+}
@@ -0,0 +1,245 @@
+use boids_core::{
+    Attractor, FlockParams, NeighbourMode, Route, Scenario, Species, Vec2,
+    goal::GoalError,
+    scenario::{InitialVelocity, ScenarioError, SpawnDistribution, SpeciesSpawn},
+    species::SpeciesError,
+};
+
+fn scenario_file(name: &str) -> String {
+    format!("{}/../scenarios/{name}", env!("CARGO_MANIFEST_DIR"))
+}
+
+fn assert_rejected(scenario: Scenario, reason: &str) {
+    match scenario.validate() {
+        Err(ScenarioError::Invalid(found)) => assert_eq!(found, reason),
+        other => panic!("expected `{reason}`, got {other:?}"),
+    }
+}
+
+#[test]
+fn toml_round_trip() {
+    let scenario = Scenario::load(scenario_file("example.toml")).unwrap();
+    assert_eq!(scenario.boid_count, 300);
+    assert_eq!(scenario.obstacles.len(), 3);
+
+    let saved = toml::to_string(&scenario).unwrap();
+    assert_eq!(Scenario::from_toml_str(&saved).unwrap(), scenario);
+}
+
+#[test]
+fn ron_round_trip() {
+    let scenario = Scenario::load(scenario_file("grid.ron")).unwrap();
+    assert_eq!(scenario.spawn, SpawnDistribution::Grid { spacing: 20. });
+    assert_eq!(
+        scenario.initial_velocity,
+        InitialVelocity::Fixed {
+            velocity: (60., 0.)
+        }
+    );
+
+    let saved = ron::to_string(&scenario).unwrap();
+    assert_eq!(Scenario::from_ron_str(&saved).unwrap(), scenario);
+}
+
+#[test]
+fn missing_fields_take_the_defaults() {
+    let scenario = Scenario::from_toml_str("boid_count = 3").unwrap();
+    assert_eq!(
+        scenario,
+        Scenario {
+            boid_count: 3,
+            ..Scenario::default()
+        }
+    );
+}
+
+#[test]
+fn rejects_world_size() {
+    assert_rejected(
+        Scenario {
+            world_size: (0., 600.),
+            ..Scenario::default()
+        },
+        "world_size must be finite and positive",
+    );
+}
+
+#[test]
+fn rejects_depth() {
+    assert_rejected(
+        Scenario {
+            depth: f32::NAN,
+            ..Scenario::default()
+        },
+        "depth must be finite and positive",
+    );
+}
+
+#[test]
+fn rejects_physics_hz() {
+    assert_rejected(
+        Scenario {
+            physics_hz: -60.,
+            ..Scenario::default()
+        },
+        "physics_hz must be finite and positive",
+    );
+}
+
+#[test]
+fn rejects_max_steps_per_frame() {
+    assert_rejected(
+        Scenario {
+            max_steps_per_frame: 0,
+            ..Scenario::default()
+        },
+        "max_steps_per_frame must be at least 1",
+    );
+}
+
+#[test]
+fn rejects_spawn_radius() {
+    for radius in [-1., f32::NAN] {
+        assert_rejected(
+            Scenario {
+                spawn: SpawnDistribution::Disc {
+                    center: None,
+                    radius,
+                },
+                ..Scenario::default()
+            },
+            "spawn radius must be finite and non negative",
+        );
+    }
+}
+
+#[test]
+fn rejects_spawn_spacing() {
+    for spacing in [0., -5.] {
+        assert_rejected(
+            Scenario {
+                spawn: SpawnDistribution::Grid { spacing },
+                ..Scenario::default()
+            },
+            "spawn spacing must be finite and positive",
+        );
+    }
+}
+
+#[test]
+fn rejects_negative_speeds() {
+    assert_rejected(
+        Scenario {
+            initial_velocity: InitialVelocity::Random {
+                min_speed: -10.,
+                max_speed: 10.,
+            },
+            ..Scenario::default()
+        },
+        "initial speeds must be finite and non negative",
+    );
+}
+
+#[test]
+fn rejects_min_speed_above_max_speed() {
+    assert_rejected(
+        Scenario {
+            initial_velocity: InitialVelocity::Random {
+                min_speed: 100.,
+                max_speed: 50.,
+            },
+            ..Scenario::default()
+        },
+        "min_speed must not be above max_speed",
+    );
+}
+
+#[test]
+fn rejects_flock_params() {
+    let mut scenario = Scenario::default();
+    scenario.params.max_speed = -1.;
+    assert!(matches!(scenario.validate(), Err(ScenarioError::Params(_))));
+}
+
+#[test]
+fn rejects_attractors() {
+    let cases = [
+        (
+            Attractor::new((f32::NAN, 0.), 1., None),
+            GoalError::NotFinite,
+        ),
+        (
+            Attractor::new((0., 0.), f32::INFINITY, None),
+            GoalError::NotFinite,
+        ),
+        (
+            Attractor::new((0., 0.), 1., Some(-5.)),
+            GoalError::NegativeRadius { radius: -5. },
+        ),
+    ];
+    for (attractor, expected) in cases {
+        let scenario = Scenario {
+            attractors: vec![attractor],
+            ..Scenario::default()
+        };
+        assert!(
+            matches!(scenario.validate(), Err(ScenarioError::Goal(ref err)) if *err == expected),
+            "{attractor:?}"
+        );
+    }
+}
+
+#[test]
+fn rejects_routes() {
+    let cases = [
+        (
+            Route {
+                waypoints: Vec::new(),
+                looping: true,
+            },
+            GoalError::EmptyRoute,
+        ),
+        (
+            Route {
+                waypoints: vec![Vec2::new(0., 0.), Vec2::new(0., f32::NAN)],
+                looping: false,
+            },
+            GoalError::NotFinite,
+        ),
+    ];
+    for (route, expected) in cases {
+        let scenario = Scenario {
+            route: Some(route.clone()),
+            ..Scenario::default()
+        };
+        assert!(
+            matches!(scenario.validate(), Err(ScenarioError::Goal(ref err)) if *err == expected),
+            "{route:?}"
+        );
+    }
+}
+
+#[test]
+fn rejects_species_with_another_neighbour_mode() {
+    let spawn = |name: &str, neighbours| SpeciesSpawn {
+        species: Species::new(
+            name,
+            FlockParams {
+                neighbours,
+                ..FlockParams::default()
+            },
+        ),
+        count: 10,
+    };
+    let scenario = Scenario {
+        species: vec![
+            spawn("metric", NeighbourMode::Metric),
+            spawn("nearest", NeighbourMode::Topological { k: 7 }),
+        ],
+        ..Scenario::default()
+    };
+    assert!(matches!(
+        scenario.validate(),
+        Err(ScenarioError::Species(SpeciesError::NeighbourMode { species })) if species == "nearest"
+    ));
+}
@@ -0,0 +1,89 @@
+use boids_core::{
+    Vec2,
+    grid::UniformGrid,
+    quadtree::{Aabb, Quadtree},
+    rng::Rng,
+    spatial::{BruteForce, SpatialIndex},
+};
+
+const WIDTH: f32 = 400.;
+const HEIGHT: f32 = 300.;
+
+fn period() -> Vec2 {
+    Vec2::new(WIDTH, HEIGHT)
+}
+
+fn random_point(rng: &mut Rng, min: Vec2, max: Vec2) -> Vec2 {
+    Vec2::new(rng.range_f32(min[0], max[0]), rng.range_f32(min[1], max[1]))
+}
+
+/// Uniform points with a dense cluster, some of them on the same spot.
+fn positions(rng: &mut Rng) -> Vec<Vec2> {
+    let cluster = (Vec2::new(50., 50.), Vec2::new(60., 60.));
+    let mut positions: Vec<_> = (0..400)
+        .map(|_| random_point(rng, Vec2::zero(), period()))
+        .collect();
+    positions.extend((0..100).map(|_| random_point(rng, cluster.0, cluster.1)));
+    positions.extend([Vec2::new(200., 150.); 5]);
+    positions
+}
+
+fn indices(positions: &[Vec2]) -> Vec<Box<dyn SpatialIndex>> {
+    let bound = Aabb::from_min_max(Vec2::zero(), period());
+    let mut indices: Vec<Box<dyn SpatialIndex>> = vec![
+        Box::new(Quadtree::new(bound)),
+        Box::new(UniformGrid::new(bound, 25.)),
+    ];
+    for index in &mut indices {
+        index.rebuild(bound, positions);
+    }
+    indices
+}
+
+#[test]
+fn indices_agree_with_brute_force() {
+    let mut rng = Rng::new(3);
+    let positions = positions(&mut rng);
+    let mut reference = BruteForce::new();
+    reference.rebuild(Aabb::from_min_max(Vec2::zero(), period()), &positions);
+
+    for _ in 0..200 {
+        let point = random_point(&mut rng, Vec2::zero(), period());
+        let range = Aabb::from_center(point, rng.range_f32(0., 250.));
+        let k = rng.range_f32(0., 20.) as usize;
+        let query = |index: &dyn SpatialIndex| {
+            let (mut range_found, mut wrapped_found) = (Vec::new(), Vec::new());
+            let (mut nearest, mut wrapped_nearest) = (Vec::new(), Vec::new());
+            index.query_range(&range, &mut range_found);
+            index.query_range_wrapped(&range, period(), &mut wrapped_found);
+            index.k_nearest(point, k, &mut nearest);
+            index.k_nearest_wrapped(point, k, period(), &mut wrapped_nearest);
+            range_found.sort_unstable();
+            (range_found, wrapped_found, nearest, wrapped_nearest)
+        };
+
+        let expected = query(&reference);
+        for index in indices(&positions) {
+            assert_eq!(query(&*index), expected, "around {point:?}, k = {k}");
+        }
+    }
+}
+
+#[test]
+fn insert_and_remove() {
+    let positions = positions(&mut Rng::new(4));
+    for mut index in indices(&positions) {
+        let mut found = Vec::new();
+        assert!(index.remove(positions[7], 7));
+        assert!(!index.remove(positions[7], 7));
+        assert!(!index.remove(positions[8], 9));
+        index.k_nearest(positions[7], 1, &mut found);
+        assert_ne!(found, [7]);
+
+        assert!(index.insert(positions[7], 7));
+        assert!(!index.insert(period() * 2., 7));
+        found.clear();
+        index.query_range(&Aabb::from_center(positions[7], 0.), &mut found);
+        assert!(found.contains(&7));
+    }
+}
@@ -0,0 +1,163 @@
+use boids_core::{
+    Boid, Boundary, FlockParams, Interaction, InteractionMatrix, NeighbourMode, Simulation,
+    Species, Vec2, World, boid::Steering, species::SpeciesError,
+};
+
+/// A boid of species 0 with one of species 1 beside it.
+fn pair() -> (Boid, Boid) {
+    let mut first = Boid::new((100., 100.), 0);
+    first.velocity = Vec2::new(60., 0.);
+    let mut second = Boid::new((120., 100.), 1);
+    second.velocity = Vec2::new(0., 60.);
+    second.set_species(1);
+    (first, second)
+}
+
+/// What the first boid of the pair gathers from the second, reacting to it with `interaction`.
+fn perceived(interaction: Interaction) -> Steering {
+    let (first, second) = pair();
+    let world = World::new((400., 400.), Boundary::default());
+    let mut steering = Steering::default();
+    steering.perceive(
+        &FlockParams::default(),
+        &world,
+        interaction,
+        first.state(),
+        second.state(),
+    );
+    steering
+}
+
+/// Neighbours counted by the first boid of the pair after a step,
+/// species 0 reacting to species 1 with `interaction`.
+fn neighbour_count(interaction: Interaction) -> u32 {
+    let (first, second) = pair();
+    let mut sim = Simulation::new(vec![first, second], (400., 400.));
+    let mut interactions = InteractionMatrix::uniform(2, Interaction::Flock);
+    interactions.set(0, 1, interaction);
+    let species = vec![
+        Species::default(),
+        Species::new("other", FlockParams::default()),
+    ];
+    sim.set_species(species, interactions).unwrap();
+    sim.step();
+    sim.boids().get(0).unwrap().neighbour_count()
+}
+
+#[test]
+fn flock_aligns_and_coheres() {
+    let params = FlockParams::default();
+    let (boid, _) = pair();
+    let steering = perceived(Interaction::Flock);
+    assert_eq!(neighbour_count(Interaction::Flock), 1);
+    assert_ne!(
+        steering.alignement_acceleration(&params, boid.velocity),
+        Vec2::default()
+    );
+    assert_ne!(
+        steering.cohesion_acceleration(&params, boid.position),
+        Vec2::default()
+    );
+}
+
+#[test]
+fn avoid_and_ignore_neither_align_nor_cohere() {
+    let params = FlockParams::default();
+    let (boid, _) = pair();
+    for interaction in [Interaction::Avoid, Interaction::Ignore] {
+        let steering = perceived(interaction);
+        assert_eq!(neighbour_count(interaction), 0, "{interaction:?}");
+        assert_eq!(
+            steering.alignement_acceleration(&params, boid.velocity),
+            Vec2::default(),
+            "{interaction:?}"
+        );
+        assert_eq!(
+            steering.cohesion_acceleration(&params, boid.position),
+            Vec2::default(),
+            "{interaction:?}"
+        );
+        // further than the separation radius, only avoidance pushes away
+        assert_eq!(
+            steering.separation_acceleration(&params) != Vec2::default(),
+            interaction == Interaction::Avoid,
+            "{interaction:?}"
+        );
+    }
+}
+
+#[test]
+fn matrix_must_be_square() {
+    let rows = vec![
+        vec![Interaction::Flock, Interaction::Avoid],
+        vec![Interaction::Ignore],
+    ];
+    assert!(matches!(
+        InteractionMatrix::from_rows(&rows),
+        Err(SpeciesError::Matrix {
+            species: 2,
+            columns: 1
+        })
+    ));
+    assert!(InteractionMatrix::from_rows(&vec![vec![Interaction::Flock; 3]; 2]).is_err());
+    assert_eq!(
+        InteractionMatrix::from_rows(&vec![vec![Interaction::Flock; 2]; 2]).unwrap(),
+        InteractionMatrix::uniform(2, Interaction::Flock)
+    );
+}
+
+#[test]
+fn boids_of_a_removed_species_fall_back_to_the_first() {
+    let boids = (0..3)
+        .map(|i| {
+            let mut boid = Boid::new((10. * i as f32, 0.), i);
+            boid.set_species(i);
+            boid
+        })
+        .collect();
+    let mut sim = Simulation::new(boids, (400., 400.));
+    let species = (0..3).map(|i| Species::new(format!("{i}"), FlockParams::default()));
+    sim.set_species(
+        species.collect(),
+        InteractionMatrix::uniform(3, Interaction::Flock),
+    )
+    .unwrap();
+
+    let species = vec![
+        Species::default(),
+        Species::new("b", FlockParams::default()),
+    ];
+    sim.set_species(species, InteractionMatrix::uniform(2, Interaction::Flock))
+        .unwrap();
+    let species: Vec<_> = sim.boids().iter().map(|boid| boid.species()).collect();
+    assert_eq!(species, [0, 1, 0]);
+}
+
+#[test]
+fn species_must_share_the_neighbour_mode() {
+    let nearest = FlockParams {
+        neighbours: NeighbourMode::Topological { k: 7 },
+        ..FlockParams::default()
+    };
+    let species = vec![Species::default(), Species::new("nearest", nearest.clone())];
+
+    let mut sim = Simulation::new(Vec::new(), (400., 400.));
+    assert!(matches!(
+        sim.set_species(species, InteractionMatrix::uniform(2, Interaction::Flock)),
+        Err(SpeciesError::NeighbourMode { species }) if species == "nearest"
+    ));
+
+    // setting the parameters of the first species sets the mode of the others
+    let species = vec![
+        Species::default(),
+        Species::new("b", FlockParams::default()),
+    ];
+    sim.set_species(species, InteractionMatrix::uniform(2, Interaction::Flock))
+        .unwrap();
+    sim.set_params(nearest).unwrap();
+    assert!(
+        sim.species()
+            .iter()
+            .all(|s| s.params.neighbours == NeighbourMode::Topological { k: 7 })
+    );
+}
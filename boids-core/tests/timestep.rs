@@ -0,0 +1,54 @@
+use boids_core::{Simulation, simulation::spawn_spiral, timestep::FixedTimestep};
+
+// a power of two, so that the sums below are exact
+const HZ: f32 = 64.;
+const DT: f32 = 1. / HZ;
+
+#[test]
+fn remainder_carries_over_to_the_next_frame() {
+    let mut timestep = FixedTimestep::new(HZ, 30);
+    assert_eq!(timestep.accumulate(3.5 * DT), 3);
+    assert_eq!(timestep.alpha(), 0.5);
+
+    assert_eq!(timestep.accumulate(0.25 * DT), 0);
+    assert_eq!(timestep.alpha(), 0.75);
+    assert_eq!(timestep.accumulate(0.25 * DT), 1);
+    assert_eq!(timestep.alpha(), 0.);
+}
+
+#[test]
+fn hitch_runs_at_most_max_steps() {
+    let mut timestep = FixedTimestep::new(HZ, 5);
+    assert_eq!(timestep.accumulate(100. * DT), 5);
+    // the time beyond the cap is dropped, not run over the next frames
+    assert!(timestep.alpha() < 1.);
+    assert_eq!(timestep.accumulate(0.), 0);
+    assert_eq!(timestep.accumulate(DT), 1);
+}
+
+#[test]
+fn negative_frame_time_runs_nothing() {
+    let mut timestep = FixedTimestep::new(HZ, 30);
+    timestep.accumulate(0.5 * DT);
+    assert_eq!(timestep.accumulate(-10.), 0);
+    assert_eq!(timestep.alpha(), 0.5);
+}
+
+#[test]
+fn max_steps_is_at_least_one() {
+    let mut timestep = FixedTimestep::new(HZ, 0);
+    assert_eq!(timestep.max_steps_per_frame(), 1);
+    assert_eq!(timestep.accumulate(10. * DT), 1);
+}
+
+#[test]
+fn simulation_advances_by_whole_steps() {
+    let mut sim = Simulation::new(spawn_spiral(10, (400., 400.)), (400., 400.));
+    sim.set_timestep(FixedTimestep::new(HZ, 4));
+
+    assert_eq!(sim.advance(2.5 * DT), 2);
+    assert_eq!(sim.alpha(), 0.5);
+    assert_eq!(sim.advance(10. * DT), 4);
+    assert_eq!(sim.step_count(), 6);
+    assert_eq!(sim.time(), 6. * f64::from(DT));
+}
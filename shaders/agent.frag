@@ -0,0 +1,43 @@
+#version 150
+
+in vec2 pixel;
+flat in vec2 center;
+flat in vec2 heading;
+flat in float radius;
+flat in vec3 tint;
+// 0 boid, 1 predator
+flat in float shape;
+
+out vec4 fragColor;
+
+
+bool in_ball(vec2 pos, float r){
+  return length(pixel - pos) <= r;
+}
+
+bool on_circle(vec2 pos, float r, float thickness){
+  float dist = length(pixel - pos);
+  return dist < r + thickness && dist > r - thickness;
+}
+
+bool on_line(vec2 a, vec2 b, float thickness){
+  vec2 ab = b - a;
+  vec2 pd = normalize(vec2(-ab.y, ab.x));
+  float proj = dot(pd, a - pixel);
+  return dot(-ab, pixel - b) > 0. && dot(ab, pixel - a) > 0. && abs(proj) <= thickness;
+}
+
+void main(){
+  bool inside;
+  if (shape > 0.5){
+    vec2 head = center + heading * (2. * radius / max(length(heading), 0.001));
+    inside = on_line(center, head, radius / 2.) || on_circle(center, radius, 1.);
+  } else {
+    inside = in_ball(center, radius);
+  }
+
+  if (!inside){
+    discard;
+  }
+  fragColor = vec4(tint, 1.);
+}
@@ -0,0 +1,36 @@
+#version 150
+
+// corner of the quad, from -1 to 1
+in vec2 corner;
+
+// per instance, in pixels from the top left of the window
+in vec2 position;
+in vec2 velocity;
+in float size;
+in vec3 color;
+// 0 boid, 1 predator
+in float kind;
+
+uniform vec2 resolution;
+
+out vec2 pixel;
+flat out vec2 center;
+flat out vec2 heading;
+flat out float radius;
+flat out vec3 tint;
+flat out float shape;
+
+void main() {
+  // a predator heading reaches twice its size ahead of it
+  float extent = (kind > 0.5 ? 2.5 * size : size) + 1.;
+  pixel = position + corner * extent;
+
+  center = position;
+  heading = velocity;
+  radius = size;
+  tint = color;
+  shape = kind;
+
+  vec2 ndc = pixel / resolution * 2. - 1.;
+  gl_Position = vec4(ndc.x, -ndc.y, 0., 1.);
+}
@@ -1,15 +1,13 @@
 #version 150
 
 uniform vec2 position;
-uniform vec2 velocity;
+uniform vec2 end;
 uniform float size;
 
-uniform float separation;
-uniform float alignement;
-uniform float cohesion;
-
 uniform float z;
 uniform vec3 color ;
+// 2 disc, 3 segment, 4 box, 5 ring, boids and predators being drawn by agent.frag
+uniform float kind;
 
 uniform float canva_z;
 uniform vec2 canva_pos;
@@ -56,18 +54,30 @@ void draw_line(vec2 pos,vec2 a, vec2 b, float thickness, vec4 color){
 
 }
 
+void draw_box(vec2 a, vec2 b, vec4 color){
+  vec2 lo = min(a, b);
+  vec2 hi = max(a, b);
+  if (all(greaterThanEqual(gl_FragCoord.xy, lo)) && all(lessThanEqual(gl_FragCoord.xy, hi))){
+    fragColor = color;
+    gl_FragDepth = z;
+  }
+}
+
 void main(){
 
   vec2 invPos = vec2(position.x,resolution.y - position.y);
-  vec2 oldPos = position - velocity * 0.5;
-  vec2 invOldPos = vec2(oldPos.x,resolution.y - oldPos.y);
+  vec2 invEnd = vec2(end.x,resolution.y - end.y);
 
   gl_FragDepth = 0.;
 
-  draw_ball(invPos, size, vec4(color,1.));
-  // draw_line(gl_FragCoord.xy,invPos, invOldPos,size/5., vec4(0.75,0.075,1.000,1.000));
-  // draw_circle(invPos, separation,1.,vec4(1.,0.,0.,0.75));
-  // draw_circle(invPos, alignement,1.,vec4(0.,0.,1.,0.75));
-  // draw_circle(invPos, cohesion,1.,vec4(0.,1.,0.,0.75));
-
+  if (kind > 4.5){
+    draw_circle(invPos, size, 1., vec4(color,1.));
+  } else if (kind > 3.5){
+    draw_box(invPos, invEnd, vec4(color,1.));
+  } else if (kind > 2.5){
+    draw_line(gl_FragCoord.xy, invPos, invEnd, size, vec4(color,1.));
+    draw_ball(invPos, size, vec4(color,1.));
+  } else {
+    draw_ball(invPos, size, vec4(color,1.));
+  }
 }
@@ -1,10 +1,9 @@
+use std::error::Error;
+
 use glium::{
     Display, backend,
     glutin::surface::WindowSurface,
-    winit::{
-        application::ApplicationHandler, error::EventLoopError, event_loop::EventLoop,
-        window::Window,
-    },
+    winit::{application::ApplicationHandler, event_loop::EventLoop, window::Window},
 };
 
 pub trait AppTrait
@@ -18,17 +17,29 @@ where
         window: Window,
         display: Display<WindowSurface>,
         user_param: Self::InitUserParam,
-    ) -> Self;
+    ) -> Result<Self, Box<dyn Error>>;
     fn draw(&mut self);
 
-    fn run(user_param: Self::InitUserParam) -> Result<(), EventLoopError> {
-        let mut event_loop = EventLoop::new().unwrap();
-        let (window, display) = backend::glutin::SimpleWindowBuilder::new()
-            .with_title("Bouncing ball !")
-            .build(&event_loop);
+    fn window_title(_user_param: &Self::InitUserParam) -> String {
+        String::from("Boids")
+    }
+
+    fn window_size(_user_param: &Self::InitUserParam) -> Option<(u32, u32)> {
+        None
+    }
+
+    fn run(user_param: Self::InitUserParam) -> Result<(), Box<dyn Error>> {
+        let mut event_loop = EventLoop::new()?;
+        let mut builder = backend::glutin::SimpleWindowBuilder::new()
+            .with_title(&Self::window_title(&user_param));
+        if let Some((width, height)) = Self::window_size(&user_param) {
+            builder = builder.with_inner_size(width, height);
+        }
+        let (window, display) = builder.build(&event_loop);
 
-        let mut app = Self::init(&mut event_loop, window, display, user_param);
+        let mut app = Self::init(&mut event_loop, window, display, user_param)?;
 
-        event_loop.run_app(&mut app)
+        event_loop.run_app(&mut app)?;
+        Ok(())
     }
 }
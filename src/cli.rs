@@ -0,0 +1,230 @@
+use std::path::PathBuf;
+
+use boids_core::{
+    Boundary, NeighbourMode, Scenario,
+    integrator::Integrator,
+    recorder::{RecordError, RecordFormat, Recorder, WhenFull},
+    scenario::ScenarioError,
+    spatial::SpatialIndexKind,
+};
+use clap::{Args, Parser, Subcommand};
+
+/// Boids flocking simulation.
+#[derive(Debug, Parser)]
+#[command(version, about)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub mode: Option<Mode>,
+
+    #[command(flatten)]
+    pub world: WorldArgs,
+
+    #[command(flatten)]
+    pub record: RecordArgs,
+
+    /// Advance the simulation by this many seconds each frame instead of the measured frame time,
+    /// making windowed runs reproducible
+    #[arg(long, value_name = "SECONDS")]
+    pub fixed_dt: Option<f32>,
+
+    /// Snapshot file written by the S key and read back by the L key
+    #[arg(long, value_name = "PATH", default_value = "snapshot.ron")]
+    pub snapshot: PathBuf,
+
+    /// Directory containing `boid.frag`, `canva.vert`, `agent.frag` and `agent.vert`
+    #[arg(long, value_name = "DIR", default_value = "./shaders")]
+    pub shaders: PathBuf,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Mode {
+    /// Run the simulation without window nor GL context
+    Headless(HeadlessArgs),
+    /// Play a recording back in the window, without running the simulation.
+    /// The window size is the world size of the other options
+    Replay(ReplayArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct WorldArgs {
+    /// Scenario file (.toml or .ron), the other options override its values
+    #[arg(short, long, value_name = "PATH", global = true)]
+    pub scenario: Option<PathBuf>,
+
+    /// Resume a 2D simulation saved in this snapshot, the other world options being ignored
+    #[arg(long, value_name = "PATH", global = true, conflicts_with = "three_d")]
+    pub resume: Option<PathBuf>,
+
+    /// Number of boids
+    #[arg(short = 'n', long = "boids", value_name = "N", global = true)]
+    pub boid_count: Option<usize>,
+
+    /// Number of predators hunting the flock
+    #[arg(long, value_name = "N", global = true)]
+    pub predators: Option<usize>,
+
+    /// Seed of the spawn distribution
+    #[arg(long, global = true)]
+    pub seed: Option<u64>,
+
+    /// World width, which is also the window width
+    #[arg(long, value_name = "PIXELS", global = true)]
+    pub width: Option<u32>,
+
+    /// World height, which is also the window height
+    #[arg(long, value_name = "PIXELS", global = true)]
+    pub height: Option<u32>,
+
+    /// World depth, for the 3D flock only
+    #[arg(long, value_name = "UNITS", global = true)]
+    pub depth: Option<f32>,
+
+    /// Simulate a 3D flock in a box, seen through an orbiting camera.
+    /// Predators, obstacles, goals and species are not simulated in 3D
+    #[arg(long = "3d", global = true)]
+    pub three_d: bool,
+
+    /// Physics steps per simulated second
+    #[arg(long, global = true)]
+    pub hz: Option<f32>,
+
+    /// Maximum number of physics steps per frame, extra time being dropped
+    #[arg(long, value_name = "STEPS", global = true)]
+    pub max_steps_per_frame: Option<u32>,
+
+    /// Integration scheme : explicit_euler, semi_implicit_euler, verlet or rk4
+    #[arg(long, global = true)]
+    pub integrator: Option<Integrator>,
+
+    /// Behaviour on the edges of the world : soft_turn, bounce or wrap
+    #[arg(long, global = true)]
+    pub boundary: Option<Boundary>,
+
+    /// Interact with the K nearest boids instead of the ones within the rule radius
+    #[arg(long, value_name = "K", global = true)]
+    pub nearest: Option<usize>,
+
+    /// How neighbours are found : quadtree, grid or brute_force
+    #[arg(long, value_name = "INDEX", global = true)]
+    pub spatial_index: Option<SpatialIndexKind>,
+}
+
+#[derive(Debug, Args)]
+pub struct RecordArgs {
+    /// Record the boid trajectories to this file, CSV for a `.csv` file and binary otherwise
+    #[arg(long, value_name = "PATH", global = true)]
+    pub record: Option<PathBuf>,
+
+    /// Record one physics step out of N
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = 1,
+        value_parser = clap::value_parser!(u32).range(1..),
+        global = true
+    )]
+    pub record_every: u32,
+
+    /// Recording format, overriding the one of the file extension : csv or binary
+    #[arg(long, value_name = "FORMAT", global = true)]
+    pub record_format: Option<RecordFormat>,
+}
+
+#[derive(Debug, Args)]
+pub struct HeadlessArgs {
+    /// Number of physics steps to simulate
+    #[arg(long, default_value_t = 1000)]
+    pub steps: u32,
+
+    /// Write per step flock metrics as CSV to this file
+    #[arg(long, value_name = "PATH")]
+    pub metrics: Option<PathBuf>,
+
+    /// Write a snapshot of the final state to this file, to resume it later
+    #[arg(long, value_name = "PATH")]
+    pub save: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct ReplayArgs {
+    /// Recording written by `--record`
+    #[arg(value_name = "PATH")]
+    pub recording: PathBuf,
+
+    /// Playback speed relative to the recorded time
+    #[arg(long, default_value_t = 1., value_parser = parse_speed)]
+    pub speed: f32,
+}
+
+impl RecordArgs {
+    pub fn recorder(&self, when_full: WhenFull) -> Result<Option<Recorder>, RecordError> {
+        let Some(path) = &self.record else {
+            return Ok(None);
+        };
+        let format = self
+            .record_format
+            .unwrap_or_else(|| RecordFormat::from_path(path));
+        Recorder::create(path, format, self.record_every, when_full).map(Some)
+    }
+}
+
+impl WorldArgs {
+    pub fn scenario(&self) -> Result<Scenario, ScenarioError> {
+        let mut scenario = match &self.scenario {
+            Some(path) => Scenario::load(path)?,
+            None => Scenario::default(),
+        };
+
+        if let Some(count) = self.boid_count {
+            scenario.boid_count = count;
+        }
+        if let Some(count) = self.predators {
+            scenario.predator_count = count;
+        }
+        if let Some(seed) = self.seed {
+            scenario.seed = seed;
+        }
+        if let Some(width) = self.width {
+            scenario.world_size.0 = width as f32;
+        }
+        if let Some(height) = self.height {
+            scenario.world_size.1 = height as f32;
+        }
+        if let Some(depth) = self.depth {
+            scenario.depth = depth;
+        }
+        if let Some(hz) = self.hz {
+            scenario.physics_hz = hz;
+        }
+        if let Some(max_steps) = self.max_steps_per_frame {
+            scenario.max_steps_per_frame = max_steps;
+        }
+        if let Some(integrator) = self.integrator {
+            scenario.integrator = integrator;
+        }
+        if let Some(boundary) = self.boundary {
+            scenario.boundary = boundary;
+        }
+        if let Some(k) = self.nearest {
+            scenario.params.neighbours = NeighbourMode::Topological { k };
+            for s in &mut scenario.species {
+                s.species.params.neighbours = NeighbourMode::Topological { k };
+            }
+        }
+        if let Some(spatial_index) = self.spatial_index {
+            scenario.spatial_index = spatial_index;
+        }
+
+        scenario.validate()?;
+        Ok(scenario)
+    }
+}
+
+fn parse_speed(s: &str) -> Result<f32, String> {
+    let speed: f32 = s.parse().map_err(|err| format!("{err}"))?;
+    if speed > 0. && speed.is_finite() {
+        Ok(speed)
+    } else {
+        Err(format!("speed must be finite and positive, got {speed}"))
+    }
+}
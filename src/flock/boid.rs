@@ -1,290 +1,176 @@
-use glium::dynamic_uniform;
-use my_glium_util::{
-    canvas::traits::CanvasDrawable,
-    datastruct::points::As2dPoint, math::{EuclidianSpace, Vec2, Vec3, VectorSpace},
-};
-
-pub struct Boid {
-    pub position: Vec2,
-    pub velocity: Vec2,
-
-    pub size: f32,
-
-    separation_force: Vec2,
-    alignement_force: Vec2,
-    cohesion_force: Vec2,
-
-    cohesion_number: f32,
-    alignement_number: f32,
-
-    border_margin: f32,
-    pub separation: f32,
-    pub alignement: f32,
-    pub cohesion: f32,
-
-    avoid_factor: f32,
-    matching_factor: f32,
-    centering_factor: f32,
-    turn_factor: f32,
-
-    color:Vec3,
-    avg_color : Vec3,
-    avg_color_nominator : Vec3,
-    avg_color_denominator : f32,
-    z: f32,
+use boids_core::{Attractor, BoidRef, Obstacle, Predator, Route};
+use glium::{dynamic_uniform, implement_vertex, uniforms::DynamicUniforms};
+
+const SCENERY_Z: f32 = 1.;
+const PREDATOR_COLOR: [f32; 3] = [1., 0.1, 0.1];
+const OBSTACLE_COLOR: [f32; 3] = [0.35, 0.35, 0.4];
+const OBSTACLE_EDGE: f32 = 2.;
+const ATTRACTOR_COLOR: [f32; 3] = [0.2, 0.9, 0.4];
+const REPULSOR_COLOR: [f32; 3] = [0.9, 0.5, 0.2];
+const ATTRACTOR_SIZE: f32 = 8.;
+const ROUTE_COLOR: [f32; 3] = [0.25, 0.4, 0.8];
+const ROUTE_EDGE: f32 = 1.;
+
+/// How a shape is drawn, passed as `kind` to `agent.frag` for boids and predators
+/// and to `boid.frag` for the others.
+#[derive(Clone, Copy)]
+enum Kind {
+    Boid = 0,
+    Predator = 1,
+    Disc = 2,
+    Segment = 3,
+    Box = 4,
+    Ring = 5,
 }
 
-impl Boid {
-    pub fn new(pos: (f32, f32),id:usize) -> Self {
-        let pos = [pos.0, pos.1].into();
-
-        let color =hue_to_rgb(id as f32 * 4. * std::f32::consts::FRAC_PI_2 / 32.).into(); 
-        Boid {
-            position: pos,
-            velocity: [0., 0.].into(),
-            size: 2.,
-
-            separation_force: Vec2::v_space_zero(),
-            alignement_force: Vec2::v_space_zero(),
-            alignement_number: 0.,
-            cohesion_force: Vec2::v_space_zero(),
-            cohesion_number: 0.,
-
-            border_margin: 50.,
-            separation: 8.,
-            alignement: 40.,
-            cohesion: 40.,
-
-            avoid_factor: 3.,
-            matching_factor: 3.,
-            centering_factor: 0.03,
-            turn_factor: 2.,
-
-            color,
-            avg_color:color,
-
-            avg_color_nominator : Vec3::v_space_zero(),
-            avg_color_denominator : 0.,
-            z: 1.,
-        }
-    }
+/// One boid or predator, a vertex of the per-instance buffer read by `agent.vert`.
+#[derive(Clone, Copy)]
+pub struct BoidInstance {
+    position: [f32; 2],
+    velocity: [f32; 2],
+    size: f32,
+    color: [f32; 3],
+    kind: f32,
 }
 
-impl CanvasDrawable for Boid {
-    fn set_z(&mut self, z: f32) {
-        self.z = z;
-    }
-
-    fn get_z(&self) -> f32 {
-        self.z
-    }
-
-    fn canvas_uniforms(&self) -> Vec<glium::uniforms::DynamicUniforms> {
-        vec![dynamic_uniform! {
-            position : self.position.as_array(),
-            velocity : self.velocity.as_array(),
-
-            separation: &self.separation,
-            alignement: &self.alignement,
-            cohesion: &self.cohesion,
-
-            size : &self.size,
-            color : self.avg_color.as_array(),
-
-            z : &self.z,
-        }]
-    }
-
-    fn update(&mut self, canva_info: &my_glium_util::canvas::CanvasData, dt: f32) {
-        let border: (f32, f32) = (
-            canva_info.size.0 * canva_info.window_resolution.0 as f32,
-            canva_info.size.1 * canva_info.window_resolution.1 as f32,
-        );
-
-        self.handle_border_colision(border);
-        self.apply_forces(dt);
-    }
-
-    fn on_window_resized(&mut self, new_size: (u32, u32)) {
-        println!("  |- Boid resized");
-        let new_size = (new_size.0 as f32, new_size.1 as f32);
-        self.handle_border_colision(new_size);
-        self.handle_border_colision(new_size);
-
-    }
+implement_vertex!(BoidInstance, position, velocity, size, color, kind);
 
+/// Everything needed to draw one piece of the scenery, copied out of the simulation.
+pub struct AgentRender {
+    position: [f32; 2],
+    // other end of a segment, or max corner of a box
+    end: [f32; 2],
+    size: f32,
+    color: [f32; 3],
+    kind: f32,
 }
 
-impl Boid {
-    pub fn handle_border_colision(&mut self, (b_x, b_y): (f32, f32)) {
-        let [x, y] = &mut self.position.as_mut_array();
-        let [v_x, v_y] = &mut self.velocity.as_mut_array();
-
-        // println!("{:?}", (b_x, b_y));
-        let size = self.size;
-
-        // Bounding box
-        if *x < size {
-            *x = size; // prevent sticking
-            *v_x = 0.;
-        } else if *x > b_x - size {
-            *x = b_x - size; // prevent sticking
-            *v_x = 0.;
-        }
-
-        if *y < size {
-            *y = size; // prevent sticking
-            *v_y = 0.;
-        } else if *y > b_y - size {
-            *y = b_y - size; // prevent sticking
-            *v_y = 0.;
-        }
-
-        if *x < self.border_margin {
-            *v_x += self.turn_factor;
-        }
-        if *x > b_x - self.border_margin {
-            *v_x -= self.turn_factor;
-        }
-        if *y < self.border_margin {
-            *v_y += self.turn_factor;
-        }
-        if *y > b_y - self.border_margin {
-            *v_y -= self.turn_factor;
+impl BoidInstance {
+    pub fn from_boid(boid: BoidRef, alpha: f32) -> Self {
+        Self {
+            position: *boid.interpolated_position(alpha).as_array(),
+            velocity: *boid.velocity().as_array(),
+            size: boid.size(),
+            color: *boid.avg_color().as_array(),
+            kind: Kind::Boid as u8 as f32,
         }
     }
 
-    pub fn reset_forces(&mut self) {
-        self.separation_force = Vec2::v_space_zero();
-        self.alignement_force = Vec2::v_space_zero();
-        self.cohesion_force = Vec2::v_space_zero();
-
-        self.alignement_number = 0.;
-        self.cohesion_number = 0.;
-        self.avg_color_nominator = Vec3::v_space_zero();
-        self.avg_color_denominator = 0.;
-    }
-
-    const MIN_SPEED : f32 = 50.;
-
-    pub fn apply_forces(&mut self, dt: f32) {
-        let speed = self.velocity.length();
-        if speed < Self::MIN_SPEED{
-            if speed <= 0.{
-                self.velocity = Vec2::from([Self::MIN_SPEED;2]) * 2.;
-            }else{
-                self.velocity = self.velocity.normalized() * Self::MIN_SPEED;
-            }
+    pub fn from_predator(predator: &Predator, size: f32, alpha: f32) -> Self {
+        Self {
+            position: *predator.interpolated_position(alpha).as_array(),
+            velocity: *predator.velocity.as_array(),
+            size,
+            color: PREDATOR_COLOR,
+            kind: Kind::Predator as u8 as f32,
         }
-
-        self.position += self.velocity * dt;
     }
 
-    pub fn handle_separation(&mut self, other: &mut Self) {
-        if self.position.distance_sq(other.position) < self.separation * self.separation {
-            self.separation_force += self.position - other.position;
-            other.separation_force += other.position - self.position;
-
-            //static collision
-            if (self.position[0] - other.position[0]) * (self.position[0] - other.position[0])
-                + (self.position[1] - other.position[1]) * (self.position[1] - other.position[1])
-                < (self.size + other.size) * (self.size + other.size)
-            {
-                let dist = self.position.distance(other.position).max(0.001);
-
-                let overlap = 0.5 * ((self.size + other.size) - dist).max(0.0);
-
-                //2. resolve overlap
-                self.position += (self.position - other.position) * overlap / dist;
-                other.position -= (self.position - other.position) * overlap / dist;
-            }
+    /// Boid drawn straight from screen coordinates, for the views without a [`BoidRef`] to read.
+    pub fn at(position: [f32; 2], velocity: [f32; 2], size: f32, color: [f32; 3]) -> Self {
+        Self {
+            position,
+            velocity,
+            size,
+            color,
+            kind: Kind::Boid as u8 as f32,
         }
     }
-    pub fn apply_separation(&mut self, dt: f32) {
-        self.velocity += self.separation_force * self.avoid_factor * dt;
-    }
-
-    pub fn handle_alignement(&mut self, other: &mut Self) {
-        if self.position.distance_sq(other.position) < self.alignement * self.cohesion {
-            self.alignement_number += 1.;
-            self.alignement_force += other.velocity;
+}
 
-            other.alignement_number += 1.;
-            other.alignement_force += self.velocity;
+impl AgentRender {
+    /// Shapes drawing `obstacle`, a polygon being drawn as its edges.
+    pub fn from_obstacle(obstacle: &Obstacle) -> Vec<Self> {
+        match obstacle {
+            Obstacle::Circle { center, radius } => vec![Self {
+                position: *center.as_array(),
+                size: *radius,
+                ..Self::shape(Kind::Disc)
+            }],
+            Obstacle::Box { min, max } => vec![Self {
+                position: *min.as_array(),
+                end: *max.as_array(),
+                ..Self::shape(Kind::Box)
+            }],
+            Obstacle::Polygon { points } => points
+                .iter()
+                .zip(points.iter().cycle().skip(1))
+                .map(|(a, b)| Self {
+                    position: *a.as_array(),
+                    end: *b.as_array(),
+                    size: OBSTACLE_EDGE,
+                    ..Self::shape(Kind::Segment)
+                })
+                .collect(),
         }
     }
 
-    pub fn apply_alignement(&mut self, dt: f32) {
-        if self.alignement_number > 0. {
-            let avg_vel = self.alignement_force / self.alignement_number;
-            self.velocity += (avg_vel - self.velocity) * self.matching_factor * dt;
+    pub fn from_attractor(attractor: &Attractor) -> Self {
+        Self {
+            position: *attractor.position.as_array(),
+            size: ATTRACTOR_SIZE,
+            color: if attractor.weight >= 0. {
+                ATTRACTOR_COLOR
+            } else {
+                REPULSOR_COLOR
+            },
+            ..Self::shape(Kind::Ring)
         }
     }
 
-    pub fn handle_cohesion(&mut self, other: &mut Self) {
-        if self.position.distance_sq(other.position) < self.cohesion * self.cohesion {
-            self.cohesion_number += 1.;
-            self.cohesion_force += other.position;
-
-            other.cohesion_number += 1.;
-            other.cohesion_force += self.position;
+    /// Segments between the successive waypoints of `route`.
+    pub fn from_route(route: &Route) -> Vec<Self> {
+        let waypoints = &route.waypoints;
+        let count = if route.looping {
+            waypoints.len()
+        } else {
+            waypoints.len().saturating_sub(1)
+        };
+        waypoints
+            .iter()
+            .zip(waypoints.iter().cycle().skip(1))
+            .take(count)
+            .map(|(a, b)| Self {
+                position: *a.as_array(),
+                end: *b.as_array(),
+                size: ROUTE_EDGE,
+                color: ROUTE_COLOR,
+                ..Self::shape(Kind::Segment)
+            })
+            .collect()
+    }
+
+    /// Thin segment between two screen points.
+    pub fn edge(a: [f32; 2], b: [f32; 2], color: [f32; 3]) -> Self {
+        Self {
+            position: a,
+            end: b,
+            size: ROUTE_EDGE,
+            color,
+            ..Self::shape(Kind::Segment)
         }
     }
 
-    pub fn apply_cohesion(&mut self, dt: f32) {
-        if self.cohesion_number > 0. {
-            let avg_pos = self.cohesion_force / self.cohesion_number;
-            self.velocity += (avg_pos - self.position) * self.centering_factor * dt;
+    fn shape(kind: Kind) -> Self {
+        Self {
+            position: [0.; 2],
+            end: [0.; 2],
+            size: 0.,
+            color: OBSTACLE_COLOR,
+            kind: kind as u8 as f32,
         }
     }
 
-    pub fn handle_color(&mut self, other: &mut Self) {
-        if self.position.distance_sq(other.position) < self.cohesion * self.cohesion {
-            let dist = self.position.distance(other.position).max(0.00001);
-            
-            self.avg_color_nominator += other.avg_color / dist ;
-            self.avg_color_denominator += dist.recip();
+    pub fn uniforms(&self) -> DynamicUniforms<'_, '_> {
+        dynamic_uniform! {
+            position : &self.position,
+            end : &self.end,
 
-            other.avg_color_nominator += self.avg_color / dist;
-            other.avg_color_denominator += dist.recip();
-        }
-    }
+            size : &self.size,
+            color : &self.color,
+            kind : &self.kind,
 
-    pub fn apply_color(&mut self){
-        if self.avg_color_denominator > 0.{   
-            self.avg_color =(self.avg_color_nominator)/(self.avg_color_denominator);
-        }else{
-            self.avg_color = self.color;
+            z : &SCENERY_Z,
         }
-
     }
 }
-
-impl As2dPoint<f32> for Boid {
-    #[inline]
-    fn x(&self) -> f32 {
-        self.position[0]
-    }
-
-    #[inline]
-    fn y(&self) -> f32 {
-        self.position[1]
-    }
-}
-
-fn hue_to_rgb(h: f32) -> [f32; 3] {
-    let h = h % (2. * std::f32::consts::PI);
-    let c = 1.0;
-    let h_prime = h / (std::f32::consts::FRAC_PI_3);
-    let x = c * (1.0 - ((h_prime % 2.0) - 1.0).abs());
-
-    match h_prime as u32 {
-        0 => [c, x, 0.0],
-        1 => [x, c, 0.0],
-        2 => [0.0, c, x],
-        3 => [0.0, x, c],
-        4 => [x, 0.0, c],
-        5 => [c, 0.0, x],
-        _ => [1.0, 0.0, 0.0], // fallback (shouldn't happen)
-    }
-}
\ No newline at end of file
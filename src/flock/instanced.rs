@@ -0,0 +1,78 @@
+use std::{cell::RefCell, error::Error, rc::Rc};
+
+use glium::{
+    Display, Frame, Program, Surface, VertexBuffer,
+    glutin::surface::WindowSurface,
+    implement_vertex,
+    index::{NoIndices, PrimitiveType},
+    uniform,
+};
+
+use super::boid::BoidInstance;
+
+/// Boids and predators written by the canvas element of the scene on each update,
+/// and drawn by the [`InstanceRenderer`].
+pub type SharedInstances = Rc<RefCell<Vec<BoidInstance>>>;
+
+/// Corner of the quad every instance is drawn on, from -1 to 1 on both axes.
+#[derive(Clone, Copy)]
+struct Corner {
+    corner: [f32; 2],
+}
+
+implement_vertex!(Corner, corner);
+
+/// Draws every boid and predator in a single draw call, each one on a quad only as large
+/// as its shape, instead of a pass over the whole canvas per boid.
+pub struct InstanceRenderer {
+    program: Program,
+    quad: VertexBuffer<Corner>,
+    /// Per-instance attributes, reallocated twice as large when the flock outgrows it.
+    instances: VertexBuffer<BoidInstance>,
+}
+
+impl InstanceRenderer {
+    const INITIAL_CAPACITY: usize = 1024;
+
+    pub fn new(display: &Display<WindowSurface>, program: Program) -> Result<Self, Box<dyn Error>> {
+        let corners = [[-1., -1.], [1., -1.], [-1., 1.], [1., 1.]].map(|corner| Corner { corner });
+        Ok(Self {
+            program,
+            quad: VertexBuffer::new(display, &corners)?,
+            instances: VertexBuffer::empty_dynamic(display, Self::INITIAL_CAPACITY)?,
+        })
+    }
+
+    pub fn draw(
+        &mut self,
+        display: &Display<WindowSurface>,
+        target: &mut Frame,
+        instances: &[BoidInstance],
+    ) -> Result<(), Box<dyn Error>> {
+        if instances.is_empty() {
+            return Ok(());
+        }
+        if instances.len() > self.instances.len() {
+            let capacity = instances.len().next_power_of_two();
+            self.instances = VertexBuffer::empty_dynamic(display, capacity)?;
+        }
+        let buffer = self
+            .instances
+            .slice(0..instances.len())
+            .expect("the buffer was grown to hold every instance");
+        buffer.write(instances);
+
+        let (width, height) = target.get_dimensions();
+        let uniforms = uniform! {
+            resolution: [width as f32, height as f32],
+        };
+        target.draw(
+            (&self.quad, buffer.per_instance()?),
+            &NoIndices(PrimitiveType::TriangleStrip),
+            &self.program,
+            &uniforms,
+            &Default::default(),
+        )?;
+        Ok(())
+    }
+}
@@ -1,26 +1,87 @@
-use boid::Boid;
-use my_glium_util::{
-    canvas::traits::CanvasDrawable,
-    datastruct::{aabb::Aabb, quadtree::Quadtree},
-};
+use std::{cell::RefCell, rc::Rc};
 
-pub mod boid;
+use boid::{AgentRender, BoidInstance};
+use boids_core::{Simulation, recorder::Recorder};
+use my_glium_util::canvas::traits::CanvasDrawable;
 
+mod boid;
+mod instanced;
+mod replay;
+mod space;
+
+pub use instanced::{InstanceRenderer, SharedInstances};
+pub use replay::{ReplayFlock, SharedReplay};
+pub use space::{Camera, Flock3, SharedCamera, SharedSimulation3};
+
+/// Simulation shared between the canvas, which steps and draws it,
+/// and the app, which edits it from the input events.
+pub type SharedSimulation = Rc<RefCell<Simulation>>;
+/// Recorder fed by the canvas after each physics step, and finished by the app on exit.
+pub type SharedRecorder = Rc<RefCell<Recorder>>;
+
+/// Rendering adapter drawing a [`Simulation`] on a canvas.
 pub struct Flock {
-    boids: Quadtree<f32,Boid, 10>,
+    sim: SharedSimulation,
+    recorder: Option<SharedRecorder>,
+    // boids and predators as of the last update,
+    // positions interpolated between the last two physics steps
+    instances: SharedInstances,
+    // obstacles and goals
+    agents: Vec<AgentRender>,
 
-    boundary: Aabb<f32>,
     z: f32,
 }
 
 impl Flock {
-    pub fn new(boids: Vec<Boid>, bound: Aabb<f32>) -> Self {
-        Self {
-            boids: Quadtree::new(bound, boids),
-
-            boundary: bound,
+    pub fn new(
+        sim: SharedSimulation,
+        recorder: Option<SharedRecorder>,
+        instances: SharedInstances,
+    ) -> Self {
+        let mut flock = Self {
+            sim,
+            recorder,
+            instances,
+            agents: Vec::new(),
             z: 0.5,
+        };
+        flock.update_render_data();
+        flock
+    }
+
+    fn update_render_data(&mut self) {
+        let sim = self.sim.borrow();
+        let alpha = sim.alpha();
+        let predator_size = sim.predator_params().size;
+
+        let mut instances = self.instances.borrow_mut();
+        instances.clear();
+        instances.extend(
+            sim.boids()
+                .iter()
+                .map(|boid| BoidInstance::from_boid(boid, alpha)),
+        );
+        instances.extend(
+            sim.predators()
+                .iter()
+                .map(|predator| BoidInstance::from_predator(predator, predator_size, alpha)),
+        );
+
+        self.agents.clear();
+        self.agents.extend(
+            sim.obstacles()
+                .iter()
+                .flat_map(AgentRender::from_obstacle),
+        );
+        if let Some(route) = sim.route() {
+            self.agents.extend(AgentRender::from_route(route));
         }
+        self.agents.extend(
+            sim.attractors()
+                .iter()
+                .chain(sim.pointer())
+                .map(AgentRender::from_attractor),
+        );
     }
 }
 
@@ -34,64 +95,25 @@ impl CanvasDrawable for Flock {
     }
 
     fn canvas_uniforms(&self) -> Vec<glium::uniforms::DynamicUniforms> {
-        let mut result = Vec::with_capacity(self.boids.len());
-        for boid in self.boids.query_range(self.boundary) {
-            let mut uni = boid.canvas_uniforms();
-            result.append(&mut uni);
-        }
-
-        result
+        self.agents
+            .iter()
+            .map(AgentRender::uniforms)
+            .collect()
     }
 
-    fn update(&mut self, canva_info: &my_glium_util::canvas::CanvasData, dt: f32) {
-        let border: (f32, f32) = (
-            (self.boundary.center.x + self.boundary.half_dim)
-                .min(canva_info.size.0 * canva_info.window_resolution.0 as f32),
-            (self.boundary.center.y + self.boundary.half_dim)
-                .min(canva_info.size.1 * canva_info.window_resolution.1 as f32),
-        );
-
-        const PHYSIC_SUB_STEP: u16 = 10;
-
-        let sub_dt = dt / f32::from(PHYSIC_SUB_STEP);
-        let boids = &mut self.boids;
-
-        let range_mapping = |boid: &Boid| {
-            Aabb::new(
-                (*boid.position.as_array()).into(),
-                boid.separation.max(boid.alignement).max(boid.cohesion),
-            )
-        };
-
-        let first_map = |boid: &mut Boid| {
-            boid.reset_forces();
-            boid.handle_border_colision(border);
-        };
-
-        let map_with_other = |boid: &mut Boid, other: &mut Boid| {
-            boid.handle_color(other);
-            boid.handle_separation(other);
-            boid.handle_alignement(other);
-            boid.handle_cohesion(other);
-        };
-
-        let last_map = |boid: &mut Boid| {
-            boid.apply_color();
-            boid.apply_separation(sub_dt);
-            boid.apply_alignement(sub_dt);
-            boid.apply_cohesion(sub_dt);
-
-            boid.apply_forces(sub_dt);
-        };
-
-        for _ in 0..PHYSIC_SUB_STEP {
-            boids.map_then_map_with_elem_in_range_then_map(
-                first_map,
-                range_mapping,
-                map_with_other,
-                last_map,
-            );
+    fn update(&mut self, _canva_info: &my_glium_util::canvas::CanvasData, dt: f32) {
+        match &self.recorder {
+            Some(recorder) => {
+                let mut recorder = recorder.borrow_mut();
+                self.sim
+                    .borrow_mut()
+                    .advance_with(dt, |sim| recorder.record(sim));
+            }
+            None => {
+                self.sim.borrow_mut().advance(dt);
+            }
         }
+        self.update_render_data();
     }
 
     fn is_absolute_coord_in(&self, _: (f32, f32)) -> bool {
@@ -103,17 +125,15 @@ impl CanvasDrawable for Flock {
     }
 
     fn on_click(&mut self, coord: (f32, f32)) {
-        let _ = self.boids.insert(Boid::new(coord,self.boids.len()));
+        self.sim.borrow_mut().add_boid(coord);
+        self.update_render_data();
     }
 
     fn on_window_resized(&mut self, new_size: (u32, u32)) {
         println!("-- Flock resized");
-        let new_bound = Aabb::from_min_max((0.,0.), (new_size.0 as f32,new_size.1 as f32));
-
-        for boid in self.boids.iter_mut() {
-            boid.on_window_resized(new_size);
-        }
-
-        let _ = self.boids.change_bounds(new_bound);
+        self.sim
+            .borrow_mut()
+            .resize((new_size.0 as f32, new_size.1 as f32));
+        self.update_render_data();
     }
 }
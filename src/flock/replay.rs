@@ -0,0 +1,107 @@
+use std::{cell::RefCell, rc::Rc};
+
+use boids_core::{boid::id_color, replay::Replay};
+use my_glium_util::canvas::traits::CanvasDrawable;
+
+use super::{
+    SharedInstances,
+    boid::{AgentRender, BoidInstance},
+};
+
+/// Replay shared between the canvas, which plays and draws it,
+/// and the app, which controls it from the input events.
+pub type SharedReplay = Rc<RefCell<Replay>>;
+
+const BOID_SIZE: f32 = 2.;
+const PROGRESS_COLOR: [f32; 3] = [0.5, 0.5, 0.55];
+const PROGRESS_HEIGHT: f32 = 4.;
+
+/// Rendering adapter drawing the frames of a [`Replay`] on a canvas,
+/// with a bar at the bottom showing how far it is.
+pub struct ReplayFlock {
+    replay: SharedReplay,
+    viewport: (f32, f32),
+    instances: SharedInstances,
+    // the progress bar
+    agents: Vec<AgentRender>,
+
+    z: f32,
+}
+
+impl ReplayFlock {
+    pub fn new(replay: SharedReplay, viewport: (f32, f32), instances: SharedInstances) -> Self {
+        let mut flock = Self {
+            replay,
+            viewport,
+            instances,
+            agents: Vec::new(),
+            z: 0.5,
+        };
+        flock.update_render_data();
+        flock
+    }
+
+    fn update_render_data(&mut self) {
+        let replay = self.replay.borrow();
+        let frame = replay.frame();
+        // colored by species when there are several, as the recording has no color
+        let by_species = frame.species.iter().any(|&species| species != 0);
+
+        let mut instances = self.instances.borrow_mut();
+        instances.clear();
+        instances.extend((0..frame.len()).map(|i| {
+            let color = if by_species {
+                id_color(frame.species[i] as usize * 4)
+            } else {
+                id_color(i)
+            };
+            BoidInstance::at(
+                *frame.positions[i].as_array(),
+                *frame.velocities[i].as_array(),
+                BOID_SIZE,
+                *color.as_array(),
+            )
+        }));
+
+        self.agents.clear();
+        let y = self.viewport.1 - PROGRESS_HEIGHT;
+        let end = (self.viewport.0 * replay.progress()).max(1.);
+        self.agents
+            .push(AgentRender::edge([0., y], [end, y], PROGRESS_COLOR));
+    }
+}
+
+impl CanvasDrawable for ReplayFlock {
+    fn set_z(&mut self, z: f32) {
+        self.z = z;
+    }
+
+    fn get_z(&self) -> f32 {
+        self.z
+    }
+
+    fn canvas_uniforms(&self) -> Vec<glium::uniforms::DynamicUniforms> {
+        self.agents
+            .iter()
+            .map(AgentRender::uniforms)
+            .collect()
+    }
+
+    fn update(&mut self, _canva_info: &my_glium_util::canvas::CanvasData, dt: f32) {
+        self.replay.borrow_mut().advance(dt);
+        self.update_render_data();
+    }
+
+    fn is_absolute_coord_in(&self, _: (f32, f32)) -> bool {
+        true
+    }
+
+    fn is_relative_coord_in(&self, _: (f32, f32)) -> bool {
+        true
+    }
+
+    fn on_window_resized(&mut self, new_size: (u32, u32)) {
+        self.viewport = (new_size.0 as f32, new_size.1 as f32);
+        self.update_render_data();
+    }
+}
@@ -0,0 +1,202 @@
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+
+use boids_core::{Simulation3, Vec3};
+use my_glium_util::canvas::traits::CanvasDrawable;
+
+use super::{
+    SharedInstances,
+    boid::{AgentRender, BoidInstance},
+};
+
+/// 3D simulation shared between the canvas, which steps and draws it,
+/// and the app, which edits it from the input events.
+pub type SharedSimulation3 = Rc<RefCell<Simulation3>>;
+/// Camera moved by the app from the input events and read by the canvas.
+pub type SharedCamera = Rc<Cell<Camera>>;
+
+const WORLD_EDGE_COLOR: [f32; 3] = [0.3, 0.3, 0.35];
+const NEAR: f32 = 1.;
+
+/// Perspective camera orbiting around `target`, y pointing down on the screen as in the 2D view.
+#[derive(Debug, Clone, Copy)]
+pub struct Camera {
+    pub target: Vec3,
+    /// Rotation around the vertical axis, 0 looking along +z.
+    pub yaw: f32,
+    /// Rotation above the horizon, positive looking down.
+    pub pitch: f32,
+    pub distance: f32,
+    /// Vertical field of view, in radians.
+    pub fov: f32,
+}
+
+impl Camera {
+    const MAX_PITCH: f32 = 1.5;
+
+    /// Camera seeing the whole box from the origin to `size`, slightly from above.
+    pub fn looking_at(size: Vec3) -> Self {
+        let fov = 60f32.to_radians();
+        Self {
+            target: size * 0.5,
+            yaw: 0.5,
+            pitch: 0.3,
+            distance: 0.5 * size[0].max(size[1]) / (fov * 0.5).tan() + size[2],
+            fov,
+        }
+    }
+
+    pub fn orbit(&mut self, yaw: f32, pitch: f32) {
+        self.yaw += yaw;
+        self.pitch = (self.pitch + pitch).clamp(-Self::MAX_PITCH, Self::MAX_PITCH);
+    }
+
+    /// Move toward the target by a `factor` of the distance, away when above 1.
+    pub fn zoom(&mut self, factor: f32) {
+        self.distance = (self.distance * factor).max(NEAR * 10.);
+    }
+
+    fn forward(&self) -> Vec3 {
+        let (sin_yaw, cos_yaw) = self.yaw.sin_cos();
+        let (sin_pitch, cos_pitch) = self.pitch.sin_cos();
+        Vec3::new(sin_yaw * cos_pitch, sin_pitch, cos_yaw * cos_pitch)
+    }
+
+    /// Screen position in pixels, depth along the view axis and pixels per world unit at
+    /// that depth of `point`, on a `viewport` of that size. `None` behind the camera.
+    pub fn project(&self, point: Vec3, viewport: (f32, f32)) -> Option<([f32; 2], f32, f32)> {
+        let forward = self.forward();
+        let right = Vec3::new(0., 1., 0.).cross(forward).normalized();
+        let down = forward.cross(right);
+
+        let eye = self.target - forward * self.distance;
+        let relative = point - eye;
+        let depth = relative.dot(forward);
+        if depth < NEAR {
+            return None;
+        }
+
+        let focal = viewport.1 * 0.5 / (self.fov * 0.5).tan();
+        let scale = focal / depth;
+        let screen = [
+            viewport.0 * 0.5 + relative.dot(right) * scale,
+            viewport.1 * 0.5 + relative.dot(down) * scale,
+        ];
+        Some((screen, depth, scale))
+    }
+}
+
+/// Rendering adapter drawing a [`Simulation3`] seen through a [`Camera`] on a canvas.
+pub struct Flock3 {
+    sim: SharedSimulation3,
+    camera: SharedCamera,
+    viewport: (f32, f32),
+    // boids, far to near
+    instances: SharedInstances,
+    // edges of the world
+    agents: Vec<AgentRender>,
+
+    z: f32,
+}
+
+impl Flock3 {
+    pub fn new(
+        sim: SharedSimulation3,
+        camera: SharedCamera,
+        viewport: (f32, f32),
+        instances: SharedInstances,
+    ) -> Self {
+        let mut flock = Self {
+            sim,
+            camera,
+            viewport,
+            instances,
+            agents: Vec::new(),
+            z: 0.5,
+        };
+        flock.update_render_data();
+        flock
+    }
+
+    fn update_render_data(&mut self) {
+        let sim = self.sim.borrow();
+        let camera = self.camera.get();
+        let alpha = sim.alpha();
+
+        self.agents.clear();
+        let size = sim.world().size;
+        let corner = |i: usize| {
+            Vec3::new(
+                if i & 1 != 0 { size[0] } else { 0. },
+                if i & 2 != 0 { size[1] } else { 0. },
+                if i & 4 != 0 { size[2] } else { 0. },
+            )
+        };
+        // corners differing by one coordinate
+        let edges = (0..8usize).flat_map(|i| [1, 2, 4].map(|bit| (i, i | bit)));
+        for (a, b) in edges.filter(|(a, b)| a != b) {
+            let a = camera.project(corner(a), self.viewport);
+            let b = camera.project(corner(b), self.viewport);
+            if let (Some((a, _, _)), Some((b, _, _))) = (a, b) {
+                self.agents.push(AgentRender::edge(a, b, WORLD_EDGE_COLOR));
+            }
+        }
+
+        let mut boids: Vec<_> = sim
+            .boids()
+            .iter()
+            .filter_map(|boid| {
+                let position = boid.interpolated_position(alpha);
+                let (screen, depth, scale) = camera.project(position, self.viewport)?;
+                let (head, _, _) = camera.project(position + boid.velocity, self.viewport)?;
+                let velocity = [head[0] - screen[0], head[1] - screen[1]];
+                // farther boids fade into the background
+                let fade = (camera.distance / depth).clamp(0.3, 1.);
+                let color = boid.color().as_array().map(|c| c * fade);
+                let size = (boid.size * scale).max(1.);
+                Some((depth, BoidInstance::at(screen, velocity, size, color)))
+            })
+            .collect();
+        boids.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+        let mut instances = self.instances.borrow_mut();
+        instances.clear();
+        instances.extend(boids.into_iter().map(|(_, instance)| instance));
+    }
+}
+
+impl CanvasDrawable for Flock3 {
+    fn set_z(&mut self, z: f32) {
+        self.z = z;
+    }
+
+    fn get_z(&self) -> f32 {
+        self.z
+    }
+
+    fn canvas_uniforms(&self) -> Vec<glium::uniforms::DynamicUniforms> {
+        self.agents
+            .iter()
+            .map(AgentRender::uniforms)
+            .collect()
+    }
+
+    fn update(&mut self, _canva_info: &my_glium_util::canvas::CanvasData, dt: f32) {
+        self.sim.borrow_mut().advance(dt);
+        self.update_render_data();
+    }
+
+    fn is_absolute_coord_in(&self, _: (f32, f32)) -> bool {
+        true
+    }
+
+    fn is_relative_coord_in(&self, _: (f32, f32)) -> bool {
+        true
+    }
+
+    fn on_window_resized(&mut self, new_size: (u32, u32)) {
+        self.viewport = (new_size.0 as f32, new_size.1 as f32);
+        self.update_render_data();
+    }
+}
@@ -1,29 +1,271 @@
+use std::{
+    cell::{Cell, RefCell},
+    error::Error,
+    fs::File,
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+    process::ExitCode,
+    rc::Rc,
+};
+
 use app::AppTrait;
-use flock::{Flock, boid::Boid};
+use boids_core::{
+    Attractor, Boundary, Obstacle, Scenario, Simulation,
+    headless::{self, HeadlessParams},
+    recorder::{Recorder, WhenFull},
+    replay::Replay,
+    snapshot,
+};
+use clap::Parser;
+use cli::{Cli, HeadlessArgs, Mode, ReplayArgs};
+use flock::{
+    Camera, Flock, Flock3, InstanceRenderer, ReplayFlock, SharedCamera, SharedInstances,
+    SharedRecorder, SharedReplay, SharedSimulation, SharedSimulation3,
+};
 use glium::{
     glutin::surface::WindowSurface, winit::{
         application::ApplicationHandler, event::{DeviceEvent, ElementState, MouseButton, WindowEvent}, event_loop, keyboard, window::Window
-    }, Display, Program, Surface
+    }, Display, Frame, Program, Surface
 };
 use my_glium_util::{
     canvas::{
         Canvas, CanvasData,
         traits::{CanvasDrawable, Drawable},
     },
-    datastruct::aabb::Aabb,
 };
 
 mod app;
+mod cli;
 mod flock;
 
-fn main() {
-    App::run(()).unwrap()
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    let result = match &cli.mode {
+        Some(Mode::Headless(args)) => run_headless(&cli, args),
+        Some(Mode::Replay(args)) => run_replay(&cli, args),
+        None => run_windowed(&cli, None),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run_windowed(cli: &Cli, replay: Option<Replay>) -> Result<(), Box<dyn Error>> {
+    if cli.world.three_d && cli.record.record.is_some() {
+        return Err("recordings only hold 2D simulations".into());
+    }
+    let config = AppConfig {
+        scenario: cli.world.scenario()?,
+        resume: cli.world.resume.as_ref().map(snapshot::load).transpose()?,
+        // the frame loop must not wait for the disk
+        recorder: cli.record.recorder(WhenFull::Skip)?,
+        three_d: cli.world.three_d,
+        snapshot: cli.snapshot.clone(),
+        replay,
+        fixed_dt: cli.fixed_dt,
+        frag_shader: read_shader(&cli.shaders, "boid.frag")?,
+        vert_shader: read_shader(&cli.shaders, "canva.vert")?,
+        agent_frag_shader: read_shader(&cli.shaders, "agent.frag")?,
+        agent_vert_shader: read_shader(&cli.shaders, "agent.vert")?,
+    };
+
+    App::run(config)
+}
+
+fn run_replay(cli: &Cli, args: &ReplayArgs) -> Result<(), Box<dyn Error>> {
+    if cli.world.three_d || cli.world.resume.is_some() || cli.record.record.is_some() {
+        return Err("a replay cannot be combined with --3d, --resume or --record".into());
+    }
+    let mut replay = Replay::load(&args.recording)?;
+    replay.set_speed(args.speed);
+    run_windowed(cli, Some(replay))
+}
+
+fn read_shader(dir: &Path, name: &str) -> Result<String, Box<dyn Error>> {
+    let path = dir.join(name);
+    std::fs::read_to_string(&path)
+        .map_err(|err| format!("could not load {} : {err}", path.display()).into())
+}
+
+fn run_headless(cli: &Cli, args: &HeadlessArgs) -> Result<(), Box<dyn Error>> {
+    if cli.world.three_d {
+        if args.save.is_some() {
+            return Err("snapshots only hold 2D simulations".into());
+        }
+        if cli.record.record.is_some() {
+            return Err("recordings only hold 2D simulations".into());
+        }
+        let params = HeadlessParams {
+            scenario: cli.world.scenario()?,
+            steps: args.steps,
+        };
+        return run_headless_3d(&params, args);
+    }
+
+    let sim = match &cli.world.resume {
+        Some(path) => snapshot::load(path)?,
+        None => cli.world.scenario()?.build()?,
+    };
+
+    let mut metrics = create_metrics(args, "step,time,center_x,center_y,average_speed")?;
+    let mut metrics_result = Ok(());
+    // nothing to stall without a window, every frame is kept
+    let mut recorder = cli.record.recorder(WhenFull::Block)?;
+    if let Some(recorder) = &mut recorder {
+        recorder.record(&sim);
+    }
+
+    let start = std::time::Instant::now();
+    let sim = headless::step_with(sim, args.steps, |step, sim| {
+        if let Some(recorder) = &mut recorder {
+            recorder.record(sim);
+        }
+        if let Some(out) = &mut metrics
+            && metrics_result.is_ok()
+        {
+            let center = sim.center_of_mass();
+            metrics_result = writeln!(
+                out,
+                "{step},{},{},{},{}",
+                sim.time(),
+                center[0],
+                center[1],
+                sim.average_speed()
+            );
+        }
+    });
+    let elapsed = start.elapsed().as_secs_f32();
+
+    metrics_result?;
+    if let Some(out) = &mut metrics {
+        out.flush()?;
+    }
+    if let Some(recorder) = &mut recorder {
+        let summary = recorder.finish()?;
+        println!("{} frames recorded", summary.recorded);
+    }
+
+    let center = sim.center_of_mass();
+    println!(
+        "{} boids, {} steps of {}s in {}s",
+        sim.len(),
+        args.steps,
+        sim.timestep().dt(),
+        elapsed
+    );
+    println!(
+        "average position : ({}, {}), average speed : {}",
+        center[0],
+        center[1],
+        sim.average_speed()
+    );
+    println!("state hash : {:016x}", sim.state_hash());
+
+    if let Some(path) = &args.save {
+        snapshot::save(&sim, path)?;
+        println!("snapshot saved to {}", path.display());
+    }
+
+    Ok(())
+}
+
+fn run_headless_3d(params: &HeadlessParams, args: &HeadlessArgs) -> Result<(), Box<dyn Error>> {
+    let mut metrics = create_metrics(args, "step,time,center_x,center_y,center_z,average_speed")?;
+    let mut metrics_result = Ok(());
+
+    let start = std::time::Instant::now();
+    let sim = headless::run_with_3d(params, |step, sim| {
+        if let Some(out) = &mut metrics
+            && metrics_result.is_ok()
+        {
+            let center = sim.center_of_mass();
+            metrics_result = writeln!(
+                out,
+                "{step},{},{},{},{},{}",
+                sim.time(),
+                center[0],
+                center[1],
+                center[2],
+                sim.average_speed()
+            );
+        }
+    })?;
+    let elapsed = start.elapsed().as_secs_f32();
+
+    metrics_result?;
+    if let Some(out) = &mut metrics {
+        out.flush()?;
+    }
+
+    let center = sim.center_of_mass();
+    println!(
+        "{} boids in 3D, {} steps of {}s in {}s",
+        sim.len(),
+        params.steps,
+        sim.timestep().dt(),
+        elapsed
+    );
+    println!(
+        "average position : ({}, {}, {}), average speed : {}",
+        center[0],
+        center[1],
+        center[2],
+        sim.average_speed()
+    );
+    println!("state hash : {:016x}", sim.state_hash());
+
+    Ok(())
+}
+
+/// CSV metrics file with its `header` line written, when asked for.
+fn create_metrics(
+    args: &HeadlessArgs,
+    header: &str,
+) -> Result<Option<BufWriter<File>>, Box<dyn Error>> {
+    let Some(path) = &args.metrics else {
+        return Ok(None);
+    };
+    let file =
+        File::create(path).map_err(|err| format!("could not create {} : {err}", path.display()))?;
+    let mut out = BufWriter::new(file);
+    writeln!(out, "{header}")?;
+    Ok(Some(out))
+}
+
+struct AppConfig {
+    scenario: Scenario,
+    /// Simulation to start from instead of building the scenario.
+    resume: Option<Simulation>,
+    recorder: Option<Recorder>,
+    three_d: bool,
+    /// File of the S and L keys.
+    snapshot: PathBuf,
+    /// Recording to play back instead of running a simulation.
+    replay: Option<Replay>,
+    fixed_dt: Option<f32>,
+    frag_shader: String,
+    vert_shader: String,
+    agent_frag_shader: String,
+    agent_vert_shader: String,
 }
 
 struct App {
     main_canva: Canvas,
+    /// Boids and predators of the scene, drawn over the canvas in one instanced draw call.
+    instances: SharedInstances,
+    renderer: InstanceRenderer,
+    scene: Scene,
+    snapshot: PathBuf,
+    recorder: Option<SharedRecorder>,
 
     dt: f32,
+    fixed_dt: Option<f32>,
     time: std::time::Instant,
     frame_nb_since_startup: u32,
     start_time: std::time::Instant,
@@ -32,10 +274,81 @@ struct App {
     benching_fps: bool,
 
     display: Display<WindowSurface>,
-    _window: Window,
+    window: Window,
 
     mouse_position: (f32, f32),
     mouse_cliking: bool,
+    obstacle_tool: ObstacleTool,
+    pointer_mode: PointerMode,
+}
+
+/// What the window shows : the 2D world, edited with the mouse,
+/// the 3D flock, seen through a camera the mouse orbits,
+/// or a recording, scrubbed with the mouse.
+enum Scene {
+    Flat(SharedSimulation),
+    Space {
+        simulation: SharedSimulation3,
+        camera: SharedCamera,
+    },
+    Replay(SharedReplay),
+}
+
+/// What the mouse cursor does to the flock.
+#[derive(Debug, Clone, Copy)]
+enum PointerMode {
+    Off,
+    Attract,
+    Repel,
+}
+
+impl PointerMode {
+    const RADIUS: f32 = 250.;
+
+    fn next(self) -> Self {
+        match self {
+            PointerMode::Off => PointerMode::Attract,
+            PointerMode::Attract => PointerMode::Repel,
+            PointerMode::Repel => PointerMode::Off,
+        }
+    }
+
+    fn attractor(self, position: (f32, f32)) -> Option<Attractor> {
+        let weight = match self {
+            PointerMode::Off => return None,
+            PointerMode::Attract => 1.,
+            PointerMode::Repel => -1.,
+        };
+        Some(Attractor::new(position, weight, Some(Self::RADIUS)))
+    }
+}
+
+/// Obstacle placed by a middle click.
+#[derive(Debug, Clone, Copy)]
+enum ObstacleTool {
+    Circle,
+    Box,
+    Polygon,
+}
+
+impl ObstacleTool {
+    const SIZE: f32 = 30.;
+
+    fn next(self) -> Self {
+        match self {
+            ObstacleTool::Circle => ObstacleTool::Box,
+            ObstacleTool::Box => ObstacleTool::Polygon,
+            ObstacleTool::Polygon => ObstacleTool::Circle,
+        }
+    }
+
+    fn place(self, position: (f32, f32)) -> Obstacle {
+        match self {
+            ObstacleTool::Circle => Obstacle::circle(position, Self::SIZE),
+            ObstacleTool::Box => Obstacle::square(position, Self::SIZE),
+            ObstacleTool::Polygon => Obstacle::regular_polygon(position, Self::SIZE, 5),
+        }
+    }
 }
 
 impl ApplicationHandler for App {
@@ -61,6 +374,12 @@ impl ApplicationHandler for App {
                     (ElementState::Pressed, keyboard::KeyCode::KeyF) => self.print_avg_fps(),
                     (ElementState::Pressed, keyboard::KeyCode::KeyD) => self.starting_fps_bench(),
                     (ElementState::Released, keyboard::KeyCode::KeyD) => self.ending_fps_bench(),
+                    (ElementState::Pressed, keyboard::KeyCode::KeyB) => self.cycle_boundary(),
+                    (ElementState::Pressed, key_code) => match &self.scene {
+                        Scene::Flat(_) => self.flat_key_pressed(key_code),
+                        Scene::Space { camera, .. } => space_key_pressed(camera, key_code),
+                        Scene::Replay(replay) => replay_key_pressed(replay, key_code),
+                    },
                     _ => (),
                 },
                 keyboard::PhysicalKey::Unidentified(_) => (),
@@ -79,13 +398,29 @@ impl ApplicationHandler for App {
                 device_id: _,
                 position,
             } => {
-                let new_pos = position.into();
+                let new_pos: (f32, f32) = position.into();
                 //draging
-                if self.mouse_cliking && self.main_canva.is_absolute_coord_in(self.mouse_position) {
+                if let Scene::Space { camera, .. } = &self.scene {
+                    if self.mouse_cliking {
+                        let mut cam = camera.get();
+                        cam.orbit(
+                            (new_pos.0 - self.mouse_position.0) * CAMERA_DRAG_SPEED,
+                            (new_pos.1 - self.mouse_position.1) * CAMERA_DRAG_SPEED,
+                        );
+                        camera.set(cam);
+                    }
+                } else if let Scene::Replay(replay) = &self.scene {
+                    if self.mouse_cliking {
+                        self.scrub(replay, new_pos.0);
+                    }
+                } else if self.mouse_cliking
+                    && self.main_canva.is_absolute_coord_in(self.mouse_position)
+                {
                     self.main_canva.on_drag(self.mouse_position.into(), new_pos);
                 }
 
                 self.mouse_position = new_pos.into();
+                self.update_pointer();
             }
             WindowEvent::MouseInput {
                 device_id: _,
@@ -94,6 +429,9 @@ impl ApplicationHandler for App {
             } => match (button, state) {
                 (MouseButton::Left, ElementState::Pressed) => {
                     self.mouse_cliking = true;
+                    if let Scene::Replay(replay) = &self.scene {
+                        self.scrub(replay, self.mouse_position.0);
+                    }
                     if self.main_canva.is_absolute_coord_in(self.mouse_position) {
                         self.main_canva.on_click(self.mouse_position);
                     }
@@ -102,6 +440,19 @@ impl ApplicationHandler for App {
                     self.mouse_cliking = false;
                     self.main_canva.on_click_release();
                 }
+                (MouseButton::Right, ElementState::Pressed) => {
+                    if let Scene::Flat(simulation) = &self.scene {
+                        simulation.borrow_mut().add_predator(self.mouse_position);
+                    }
+                }
+                (MouseButton::Middle, ElementState::Pressed) => {
+                    if let Scene::Flat(simulation) = &self.scene {
+                        let obstacle = self.obstacle_tool.place(self.mouse_position);
+                        if let Err(err) = simulation.borrow_mut().add_obstacle(obstacle) {
+                            eprintln!("error: {err}");
+                        }
+                    }
+                }
                 _ => (),
             },
 
@@ -123,12 +474,21 @@ impl ApplicationHandler for App {
     fn exiting(&mut self, event_loop: &event_loop::ActiveEventLoop) {
         println!("exiting...");
         self.print_avg_fps();
+        if let Some(recorder) = &self.recorder {
+            match recorder.borrow_mut().finish() {
+                Ok(summary) => println!(
+                    "{} frames recorded, {} skipped",
+                    summary.recorded, summary.skipped
+                ),
+                Err(err) => eprintln!("error: {err}"),
+            }
+        }
         event_loop.exit();
     }
 
     fn new_events(
         &mut self,
-        _event_loop: &event_loop::ActiveEventLoop,
+        event_loop: &event_loop::ActiveEventLoop,
         cause: glium::winit::event::StartCause,
     ) {
         match cause {
@@ -140,10 +500,14 @@ impl ApplicationHandler for App {
                 self.time = now;
                 self.frame_nb_since_startup += 1;
 
-                self.main_canva.update(&DUMMY_CANVA_INFO, self.dt);
+                let sim_dt = self.fixed_dt.unwrap_or(self.dt);
+                self.main_canva.update(&DUMMY_CANVA_INFO, sim_dt);
 
                 //draw
-                self.draw();
+                if let Err(err) = self.draw() {
+                    eprintln!("error: {err}");
+                    event_loop.exit();
+                }
 
                 if self.benching_fps {
                     self.frame_nb_since_f += 1;
@@ -155,21 +519,58 @@ impl ApplicationHandler for App {
 }
 
 impl AppTrait for App {
-    type InitUserParam = ();
+    type InitUserParam = AppConfig;
+
+    fn window_title(config: &AppConfig) -> String {
+        let mode = if config.replay.is_some() {
+            "Boids replay"
+        } else if config.three_d {
+            "Boids 3D"
+        } else {
+            "Boids"
+        };
+        let count = match (&config.replay, &config.resume) {
+            (Some(replay), _) => replay.frame().len(),
+            (None, Some(sim)) => sim.len(),
+            (None, None) => config.scenario.total_boid_count(),
+        };
+        format!("{mode} - {count} boids")
+    }
+
+    fn window_size(config: &AppConfig) -> Option<(u32, u32)> {
+        let (width, height) = match &config.resume {
+            Some(sim) => sim.world_size(),
+            None => config.scenario.world_size,
+        };
+        Some((width as u32, height as u32))
+    }
 
     fn init(
         event_loop: &mut event_loop::EventLoop<()>,
         window: Window,
         display: Display<WindowSurface>,
-        _user_param: Self::InitUserParam,
-    ) -> Self {
-        let frag_shad = std::fs::read_to_string("./shaders/boid.frag")
-            .expect("could not load ./shaders/ball.frag");
-        let vert_shad = std::fs::read_to_string("./shaders/canva.vert")
-            .expect("could not load ./shaders/ball.vert");
-        let program = Program::from_source(&display, &vert_shad, &frag_shad, None)
-            .expect("could not compile shaders");
-    
+        config: Self::InitUserParam,
+    ) -> Result<Self, Box<dyn Error>> {
+        let AppConfig {
+            mut scenario,
+            resume,
+            recorder,
+            three_d,
+            snapshot,
+            replay,
+            fixed_dt,
+            frag_shader,
+            vert_shader,
+            agent_frag_shader,
+            agent_vert_shader,
+        } = config;
+        let program = Program::from_source(&display, &vert_shader, &frag_shader, None)
+            .map_err(|err| format!("could not compile shaders : {err}"))?;
+        let agent_program =
+            Program::from_source(&display, &agent_vert_shader, &agent_frag_shader, None)
+                .map_err(|err| format!("could not compile shaders : {err}"))?;
+        let renderer = InstanceRenderer::new(&display, agent_program)?;
+        let instances = SharedInstances::default();
 
         let mut main_canva = Canvas::new((0., 0.), program);
 
@@ -178,20 +579,59 @@ impl AppTrait for App {
             window.inner_size().height as f32,
         );
 
-        let boids = (0..10).map(|i|Boid::new((
-            r1 / 2. + i as f32 * 4. + f32::cos(i as f32) * (r2 / 150.),
-            r2 / 2. + i as f32 * 4. + f32::sin(i as f32) * (r1 / 150.),
-        ),i)).collect();
-
-        let flock = Box::new(Flock::new(boids, Aabb::from_min_max((0., 0.), (r1, r2))));
-        main_canva.push_elem(flock);
+        // the window manager may not honor the requested size
+        scenario.world_size = (r1, r2);
+        let recorder = recorder.map(|recorder| Rc::new(RefCell::new(recorder)));
+        let scene = if let Some(replay) = replay {
+            let replay = Rc::new(RefCell::new(replay));
+            let flock = ReplayFlock::new(Rc::clone(&replay), (r1, r2), Rc::clone(&instances));
+            main_canva.push_elem(Box::new(flock));
+            Scene::Replay(replay)
+        } else if three_d {
+            let simulation = Rc::new(RefCell::new(scenario.build_3d()?));
+            let camera = Rc::new(Cell::new(Camera::looking_at(
+                simulation.borrow().world().size,
+            )));
+            let flock = Flock3::new(
+                Rc::clone(&simulation),
+                Rc::clone(&camera),
+                (r1, r2),
+                Rc::clone(&instances),
+            );
+            main_canva.push_elem(Box::new(flock));
+            Scene::Space { simulation, camera }
+        } else {
+            let simulation = match resume {
+                Some(mut sim) => {
+                    if sim.world_size() != (r1, r2) {
+                        sim.resize((r1, r2));
+                    }
+                    sim
+                }
+                None => scenario.build()?,
+            };
+            let simulation = Rc::new(RefCell::new(simulation));
+            let flock = Box::new(Flock::new(
+                Rc::clone(&simulation),
+                recorder.clone(),
+                Rc::clone(&instances),
+            ));
+            main_canva.push_elem(flock);
+            Scene::Flat(simulation)
+        };
 
         event_loop.set_control_flow(event_loop::ControlFlow::Poll);
 
-        App {
+        Ok(App {
             main_canva,
+            instances,
+            renderer,
+            scene,
+            snapshot,
+            recorder,
 
             dt: 0.,
+            fixed_dt,
             time: std::time::Instant::now(),
             frame_nb_since_startup: 0,
             start_time: std::time::Instant::now(),
@@ -199,24 +639,126 @@ impl AppTrait for App {
             frame_nb_since_f: 0,
             benching_fps: false,
             display,
-            _window: window,
+            window,
 
             mouse_position: (0., 0.),
             mouse_cliking: false,
-        }
+            obstacle_tool: ObstacleTool::Circle,
+            pointer_mode: PointerMode::Off,
+        })
     }
 
-    fn draw(&mut self) {
+    fn draw(&mut self) -> Result<(), Box<dyn Error>> {
         let mut target = self.display.draw();
 
         target.clear_color(0.03, 0.03, 0.03, 1.);
-        self.main_canva.draw(&self.display, &mut target).unwrap();
+        let drawn = self.draw_scene(&mut target);
+        // the frame must be finished even when drawing it failed
+        target.finish()?;
+        drawn
+    }
 
-        target.finish().unwrap()
+    fn draw_scene(&mut self, target: &mut Frame) -> Result<(), Box<dyn Error>> {
+        self.main_canva
+            .draw(&self.display, target)
+            .map_err(|err| format!("could not draw the canvas : {err:?}"))?;
+        self.renderer
+            .draw(&self.display, target, &self.instances.borrow())
     }
 }
 
 impl App {
+    fn update_pointer(&self) {
+        if let Scene::Flat(simulation) = &self.scene {
+            let pointer = self.pointer_mode.attractor(self.mouse_position);
+            simulation.borrow_mut().set_pointer(pointer);
+        }
+    }
+
+    fn cycle_boundary(&self) {
+        let next = |boundary| match boundary {
+            Boundary::SoftTurn => Boundary::Bounce,
+            Boundary::Bounce => Boundary::Wrap,
+            Boundary::Wrap => Boundary::SoftTurn,
+        };
+        let boundary = match &self.scene {
+            Scene::Flat(simulation) => {
+                let mut sim = simulation.borrow_mut();
+                let boundary = next(sim.boundary());
+                sim.set_boundary(boundary);
+                boundary
+            }
+            Scene::Space { simulation, .. } => {
+                let mut sim = simulation.borrow_mut();
+                let boundary = next(sim.boundary());
+                sim.set_boundary(boundary);
+                boundary
+            }
+            Scene::Replay(_) => return,
+        };
+        println!("boundary : {boundary}");
+    }
+
+    /// Show the frame at the fraction `x` is of the window width.
+    fn scrub(&self, replay: &SharedReplay, x: f32) {
+        let (width, _) = self.display.get_framebuffer_dimensions();
+        replay.borrow_mut().seek(x / width.max(1) as f32);
+    }
+
+    /// Tools editing the 2D world.
+    fn flat_key_pressed(&mut self, key_code: keyboard::KeyCode) {
+        match key_code {
+            keyboard::KeyCode::KeyO => {
+                self.obstacle_tool = self.obstacle_tool.next();
+                println!("obstacle tool : {:?}", self.obstacle_tool);
+            }
+            keyboard::KeyCode::KeyM => {
+                self.pointer_mode = self.pointer_mode.next();
+                println!("pointer : {:?}", self.pointer_mode);
+                self.update_pointer();
+            }
+            keyboard::KeyCode::KeyC => {
+                if let Scene::Flat(simulation) = &self.scene {
+                    simulation.borrow_mut().clear_obstacles();
+                }
+            }
+            keyboard::KeyCode::KeyS => self.save_snapshot(),
+            keyboard::KeyCode::KeyL => self.load_snapshot(),
+            _ => (),
+        }
+    }
+
+    fn save_snapshot(&self) {
+        let Scene::Flat(simulation) = &self.scene else {
+            return;
+        };
+        match snapshot::save(&simulation.borrow(), &self.snapshot) {
+            Ok(()) => println!("snapshot saved to {}", self.snapshot.display()),
+            Err(err) => eprintln!("error: {err}"),
+        }
+    }
+
+    /// Replace the simulation by the one of the snapshot file, keeping it on failure.
+    fn load_snapshot(&self) {
+        let Scene::Flat(simulation) = &self.scene else {
+            return;
+        };
+        match snapshot::load(&self.snapshot) {
+            Ok(mut loaded) => {
+                // the snapshot may have been saved with another window size
+                let size = self.window.inner_size();
+                let size = (size.width as f32, size.height as f32);
+                if loaded.world_size() != size {
+                    loaded.resize(size);
+                }
+                *simulation.borrow_mut() = loaded;
+                println!("snapshot loaded from {}", self.snapshot.display());
+                self.update_pointer();
+            }
+            Err(err) => eprintln!("error: {err}"),
+        }
+    }
+
     fn print_avg_fps(&self) {
         println!(
             "average fps since startup :{}",
@@ -246,6 +788,52 @@ impl App {
     }
 }
 
+const CAMERA_DRAG_SPEED: f32 = 0.005;
+const CAMERA_KEY_STEP: f32 = 0.05;
+const CAMERA_ZOOM_STEP: f32 = 1.1;
+
+/// Arrows orbit the camera, page up and down zoom.
+fn space_key_pressed(camera: &SharedCamera, key_code: keyboard::KeyCode) {
+    let mut cam = camera.get();
+    match key_code {
+        keyboard::KeyCode::ArrowLeft => cam.orbit(-CAMERA_KEY_STEP, 0.),
+        keyboard::KeyCode::ArrowRight => cam.orbit(CAMERA_KEY_STEP, 0.),
+        keyboard::KeyCode::ArrowUp => cam.orbit(0., -CAMERA_KEY_STEP),
+        keyboard::KeyCode::ArrowDown => cam.orbit(0., CAMERA_KEY_STEP),
+        keyboard::KeyCode::PageUp => cam.zoom(CAMERA_ZOOM_STEP.recip()),
+        keyboard::KeyCode::PageDown => cam.zoom(CAMERA_ZOOM_STEP),
+        _ => return,
+    }
+    camera.set(cam);
+}
+
+/// Space pauses, arrows step frame by frame and change the speed,
+/// home and end jump to the first and last frames.
+fn replay_key_pressed(replay: &SharedReplay, key_code: keyboard::KeyCode) {
+    let mut replay = replay.borrow_mut();
+    match key_code {
+        keyboard::KeyCode::Space => {
+            let paused = !replay.is_paused();
+            replay.set_paused(paused);
+        }
+        keyboard::KeyCode::ArrowLeft => replay.step_by(-1),
+        keyboard::KeyCode::ArrowRight => replay.step_by(1),
+        keyboard::KeyCode::ArrowUp => {
+            let speed = replay.speed() * 2.;
+            replay.set_speed(speed);
+            println!("replay speed : {}", replay.speed());
+        }
+        keyboard::KeyCode::ArrowDown => {
+            let speed = replay.speed() / 2.;
+            replay.set_speed(speed);
+            println!("replay speed : {}", replay.speed());
+        }
+        keyboard::KeyCode::Home => replay.seek(0.),
+        keyboard::KeyCode::End => replay.seek(1.),
+        _ => (),
+    }
+}
+
 const DUMMY_CANVA_INFO: CanvasData = CanvasData {
     size: (0., 0.),
     position: (0., 0.),